@@ -1,14 +1,88 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
 use syn::{
-    parse_macro_input, Attribute, Fields, FnArg, ItemImpl, ItemStruct, PatType, ReturnType, Type,
+    meta::ParseNestedMeta, parse_macro_input, Attribute, Fields, FnArg, GenericArgument, ItemImpl,
+    ItemStruct, LitStr, Pat, PatTupleStruct, PatType, PathArguments, ReturnType, Signature, Type,
     TypePath,
 };
 
-pub fn mark_agent_struct(input: TokenStream) -> TokenStream {
+use crate::operation_macro::{doc_comment, parse_operation_attr};
+
+/// Arguments of `#[agent(name = "...", namespace = "...", version = "...")]`
+#[derive(Default)]
+struct AgentArgs {
+    name: Option<LitStr>,
+    namespace: Option<LitStr>,
+    version: Option<LitStr>,
+}
+
+impl AgentArgs {
+    fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        let value = if meta.path.is_ident("name") {
+            &mut self.name
+        } else if meta.path.is_ident("namespace") {
+            &mut self.namespace
+        } else if meta.path.is_ident("version") {
+            &mut self.version
+        } else {
+            return Err(
+                meta.error("unsupported agent argument, expected `name`, `namespace` or `version`")
+            );
+        };
+        let lit: LitStr = meta.value()?.parse()?;
+        // `.` separates namespace segments and operation, `@` introduces the version
+        let forbidden: &[char] = if meta.path.is_ident("name") {
+            &['.', '@']
+        } else {
+            &['@']
+        };
+        if lit.value().is_empty() || lit.value().contains(forbidden) {
+            return Err(syn::Error::new(
+                lit.span(),
+                format!("must be non-empty and must not contain {:?}", forbidden),
+            ));
+        }
+        *value = Some(lit);
+        Ok(())
+    }
+}
+
+/// Arguments of `#[inject(name = "...", lazy)]` on agent fields
+#[derive(Default)]
+struct InjectArgs {
+    name: Option<LitStr>,
+    lazy: bool,
+}
+
+impl InjectArgs {
+    fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("name") {
+            self.name = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("lazy") {
+            self.lazy = true;
+        } else {
+            return Err(meta.error("unsupported inject argument, expected `name` or `lazy`"));
+        }
+        Ok(())
+    }
+}
+
+pub fn mark_agent_struct(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let mut args = AgentArgs::default();
+    let parser = syn::meta::parser(|meta| args.parse(meta));
+    parse_macro_input!(attr with parser);
+
     let s = parse_macro_input!(input as ItemStruct);
     let struct_ident = &s.ident;
 
+    let name = match &args.name {
+        Some(name) => quote! { #name },
+        None => quote! { stringify!(#struct_ident) },
+    };
+    let namespace = option_tokens(args.namespace.map(|lit| lit.value()));
+    let version = option_tokens(args.version.map(|lit| lit.value()));
+
     // Adds #[derive(Default)] if not present
     let mut struct_with_default = s.clone();
     if !has_default_derive(&s.attrs) {
@@ -17,23 +91,65 @@ pub fn mark_agent_struct(input: TokenStream) -> TokenStream {
             .push(syn::parse_quote!(#[derive(Default)]));
     }
 
-    // generates injections calls self.<field>.from_context(...)
-    let injections = if let Fields::Named(fields_named) = &s.fields {
-        fields_named
-            .named
-            .iter()
-            .filter_map(|field| {
-                let ident = field.ident.as_ref()?;
-                if is_inject_type(&field.ty) {
-                    Some(quote! { self.#ident.from_context(context); })
-                } else {
-                    None
+    let mut inject_fields = Vec::new();
+    if let Fields::Named(fields_named) = &mut struct_with_default.fields {
+        for field in fields_named.named.iter_mut() {
+            // #[inject(...)] is consumed here, the struct is emitted without it
+            let (inject_attrs, attrs) = field
+                .attrs
+                .drain(..)
+                .partition::<Vec<_>, _>(|attr| attr.path().is_ident("inject"));
+            field.attrs = attrs;
+            let mut inject_args = InjectArgs::default();
+            for attr in &inject_attrs {
+                if let Err(err) = attr.parse_nested_meta(|meta| inject_args.parse(meta)) {
+                    return err.to_compile_error().into();
                 }
-            })
-            .collect::<Vec<_>>()
-    } else {
-        vec![]
-    };
+            }
+            match (&field.ident, inject_kind(&field.ty)) {
+                (Some(ident), Some(kind)) => {
+                    if kind == "InjectAll" && (inject_args.name.is_some() || inject_args.lazy) {
+                        return syn::Error::new_spanned(
+                            &field.ty,
+                            "`InjectAll<T>` fields accept neither `name` nor `lazy`",
+                        )
+                        .to_compile_error()
+                        .into();
+                    }
+                    inject_fields.push((ident.clone(), field.ty.clone(), inject_args));
+                }
+                _ if !inject_attrs.is_empty() => {
+                    return syn::Error::new_spanned(
+                        &field.ty,
+                        "#[inject] requires an `Inject<T>` field",
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // generates injections calls self.<field>.from_context(...)
+    let injections = inject_fields
+        .iter()
+        .map(|(ident, _, args)| match (&args.name, args.lazy) {
+            (Some(name), true) => quote! { self.#ident.from_context_lazy(context, Some(#name)); },
+            (None, true) => quote! { self.#ident.from_context_lazy(context, None); },
+            (Some(name), false) => quote! { self.#ident.from_context_named(context, #name); },
+            (None, false) => quote! { self.#ident.from_context(context); },
+        });
+
+    // generates dependency declarations <Inject<T>>::dependency("<field>")
+    let dependencies = inject_fields.iter().map(|(ident, ty, args)| {
+        let field = ident.to_string();
+        let named = args.name.as_ref().map(|name| quote! { .named(#name) });
+        let lazy = args.lazy.then(|| quote! { .lazy() });
+        quote! { <#ty>::dependency(#field) #named #lazy }
+    });
+
+    let description = option_tokens(doc_comment(&s.attrs));
 
     let gen = quote! {
         #struct_with_default
@@ -42,6 +158,26 @@ pub fn mark_agent_struct(input: TokenStream) -> TokenStream {
             pub fn do_inject_dependencies(&self, context: &crate::AxorContext) {
                 #(#injections)*
             }
+
+            pub fn do_dependencies(&self) -> Vec<crate::Dependency> {
+                vec![#(#dependencies),*]
+            }
+
+            pub fn do_name(&self) -> &'static str {
+                #name
+            }
+
+            pub fn do_namespace(&self) -> Option<&'static str> {
+                #namespace
+            }
+
+            pub fn do_version(&self) -> Option<&'static str> {
+                #version
+            }
+
+            pub fn do_description(&self) -> Option<&'static str> {
+                #description
+            }
         }
     };
 
@@ -51,7 +187,18 @@ pub fn mark_agent_struct(input: TokenStream) -> TokenStream {
 /// Macro #[agent_impl] sur un impl
 /// - generates  Agent impl
 /// - calls do_inject_dependencies(context) on impl generated by agent macro
-pub fn expand_agent_impl(input: TokenStream) -> TokenStream {
+pub fn expand_agent_impl(attr: TokenStream, input: TokenStream) -> TokenStream {
+    // `#[agent_impl(schema)]` describes operations with JSON Schemas, see `ParamsSchema`
+    let mut schema = false;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("schema") {
+            schema = true;
+            Ok(())
+        } else {
+            Err(meta.error("unsupported agent_impl argument, expected `schema`"))
+        }
+    });
+    parse_macro_input!(attr with parser);
     let item_impl = parse_macro_input!(input as ItemImpl);
 
     let self_ty = &item_impl.self_ty;
@@ -60,111 +207,283 @@ pub fn expand_agent_impl(input: TokenStream) -> TokenStream {
     } else {
         panic!("#[agent_impl] must be used on an impl of a struct");
     };
-    let mut match_arms = Vec::new();
-    let mut op_names = Vec::new();
+    let mut name_arms = Vec::new();
+    let mut async_name_arms = Vec::new();
+    let mut index_arms = Vec::new();
+    let mut async_index_arms = Vec::new();
+    let mut has_async = false;
+    let mut descriptors = Vec::new();
+    let mut on_service_change = None;
+    let mut hooks = Vec::new();
 
     for item in &item_impl.items {
         if let syn::ImplItem::Fn(method) = item {
-            if !has_operation_attr(&method.attrs) {
-                continue;
+            if has_attr(&method.attrs, "on_service_change") {
+                on_service_change = Some(method.sig.ident.clone());
             }
-
-            let ident = &method.sig.ident;
-            let op_name = ident.to_string();
-            op_names.push(op_name.clone());
-
-            let inputs: Vec<_> = method.sig.inputs.iter().skip(1).collect(); // skip &self
-            eprintln!(
-                "→ Found #[operation] method: {} (args: {})",
-                ident,
-                inputs.len()
-            );
-            let (arg_decl, call_expr) = match inputs.len() {
-                0 => (quote! {}, quote! { self.#ident() }),
-                1 => {
-                    let ty = match &inputs[0] {
-                        FnArg::Typed(PatType { ty, .. }) => ty,
-                        _ => continue,
-                    };
-                    (
-                        quote! {
-                            let arg0: #ty = match payload.input_as() {
-                                Some(val) => val,
-                                None => return crate::InvokeResult {
-                                    operation: #op_name.into(),
-                                    success: false,
-                                    data: None,
-                                }
-                            };
-                        },
-                        quote! { self.#ident(arg0) },
+            for hook in LIFECYCLE_HOOKS {
+                if !has_attr(&method.attrs, hook) {
+                    continue;
+                }
+                if hooks.iter().any(|(name, _)| name == hook) {
+                    return syn::Error::new_spanned(
+                        &method.sig,
+                        format!("only one method can be marked #[{}]", hook),
                     )
+                    .to_compile_error()
+                    .into();
                 }
-                _ => continue, // trop de paramètres
+                hooks.push((*hook, lifecycle_hook(hook, &method.sig)));
+            }
+            let args = match parse_operation_attr(&method.attrs) {
+                Ok(Some(args)) => args,
+                Ok(None) => continue,
+                Err(error) => return error.to_compile_error().into(),
             };
 
-            let has_return = !matches!(method.sig.output, ReturnType::Default);
+            let ident = &method.sig.ident;
+            let op_name = args.name.clone().unwrap_or_else(|| ident.to_string());
+            let description =
+                option_tokens(args.description.or_else(|| doc_comment(&method.attrs)));
+            let deprecated = args.deprecated
+                || method
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path().is_ident("deprecated"));
+            let tags = &args.tags;
+            let hidden = args.hidden;
 
-            let match_arm = if has_return {
+            let params = operation_params(&method.sig);
+            let param_names: Vec<_> = params.iter().map(|(name, _)| name.clone()).collect();
+            let param_types: Vec<_> = params.iter().map(|(_, ty)| type_name(ty)).collect();
+            let output = output_type(&method.sig.output);
+            let output_type = match output {
+                Some(ty) => {
+                    let name = type_name(ty);
+                    quote! { Some(#name) }
+                }
+                None => quote! { None },
+            };
+            let (input_schema, output_schema) = if schema {
+                schemas(&params, output)
+            } else {
+                (quote! { None }, quote! { None })
+            };
+            let param_tys: Vec<_> = params.iter().map(|(_, ty)| ty).collect();
+            // Only inputs and bodies are listed, other parameters come from the invocation
+            let descriptor_params = if params.is_empty() {
+                quote! { vec![] }
+            } else {
                 quote! {
-                    #op_name => {
-                        #arg_decl
-                        let result = #call_expr;
-                        let data = serde_json::to_value(result).ok();
-                        crate::InvokeResult {
-                            operation: #op_name.into(),
-                            success: true,
-                            data,
-                        }
-                    }
+                    [
+                        #( (
+                            crate::ParamDescriptor { name: #param_names, type_name: #param_types },
+                            <#param_tys as crate::FromInvocation>::LISTED,
+                        ) ),*
+                    ]
+                    .into_iter()
+                    .filter_map(|(param, input)| input.then_some(param))
+                    .collect()
+                }
+            };
+            descriptors.push(quote! {
+                crate::OperationDescriptor {
+                    name: #op_name,
+                    params: #descriptor_params,
+                    output_type: #output_type,
+                    input_schema: #input_schema,
+                    output_schema: #output_schema,
+                    description: #description,
+                    deprecated: #deprecated,
+                    tags: vec![#(#tags),*],
+                    hidden: #hidden,
                 }
+            });
+
+            let args: Vec<_> = (0..params.len())
+                .map(|i| format_ident!("arg{}", i))
+                .collect();
+            // Each parameter is extracted with `FromInvocation`: inputs are decoded from the
+            // payload data, the whole of it for a single input, else by name or by position
+            let arg_decls = params.iter().enumerate().map(|(i, (_, ty))| {
+                let arg = &args[i];
+                quote! {
+                    let #arg: #ty = match crate::FromInvocation::from_invocation(&invocation, #i) {
+                        Ok(val) => val,
+                        Err(error) => return crate::InvokeResult::err(#op_name, error),
+                    };
+                }
+            });
+            let arg_decl = if params.is_empty() {
+                quote! {}
             } else {
                 quote! {
-                    #op_name => {
-                        #arg_decl
-                        #call_expr;
-                        crate::InvokeResult {
-                            operation: #op_name.into(),
-                            success: true,
-                            data: None,
-                        }
-                    }
+                    const PARAMS: &[&str] = &[#(#param_names),*];
+                    let inputs = [#(<#param_tys as crate::FromInvocation>::INPUT),*];
+                    let invocation = crate::Invocation::new(payload, PARAMS, &inputs);
+                    #(#arg_decls)*
                 }
             };
+            let call_expr = quote! { self.#ident(#(#args),*) };
 
-            match_arms.push(match_arm);
+            let is_async = method.sig.asyncness.is_some();
+            let await_expr = if is_async {
+                quote! { #call_expr.await }
+            } else {
+                quote! { #call_expr }
+            };
+
+            let invoke_body = match &method.sig.output {
+                ReturnType::Default => quote! {
+                    #arg_decl
+                    #await_expr;
+                    crate::InvokeResult::ok(#op_name, None)
+                },
+                ReturnType::Type(_, ty) if is_result_type(ty) => quote! {
+                    #arg_decl
+                    match #await_expr {
+                        Ok(result) => crate::InvokeResult::from_output(#op_name, &result),
+                        Err(error) => crate::InvokeResult::err(
+                            #op_name,
+                            crate::IntoInvokeError::into_invoke_error(error),
+                        ),
+                    }
+                },
+                ReturnType::Type(..) => quote! {
+                    #arg_decl
+                    let result = #await_expr;
+                    crate::InvokeResult::from_output(#op_name, &result)
+                },
+            };
+
+            // Hidden operations stay callable directly, but are not routed
+            if hidden {
+                continue;
+            }
+            // Index of the operation in `operations()`
+            let index = descriptors.len() - 1;
+            name_arms.push(quote! {
+                #op_name => self.call_operation_at(#index, payload)
+            });
+            async_name_arms.push(quote! {
+                #op_name => self.call_operation_async_at(#index, payload).await
+            });
+            if is_async {
+                has_async = true;
+                index_arms.push(quote! {
+                    #index => crate::InvokeResult::err(
+                        #op_name,
+                        crate::InvokeError::async_operation(#op_name),
+                    )
+                });
+            } else {
+                index_arms.push(quote! {
+                    #index => { #invoke_body }
+                });
+            }
+            async_index_arms.push(quote! {
+                #index => { #invoke_body }
+            });
         }
     }
 
+    let not_found = quote! {
+        _ => crate::InvokeResult::err(
+            payload.name.to_string(),
+            crate::InvokeError::operation_not_found(&payload.name),
+        ),
+    };
+
+    // Sync-only agents keep the default async dispatch
+    let call_operation_async = if has_async {
+        quote! {
+            async fn call_operation_async(&self, payload: &crate::Payload) -> crate::InvokeResult {
+                match payload.op_name_unchecked() {
+                    #(#async_name_arms,)*
+                    #not_found
+                }
+            }
+
+            async fn call_operation_async_at(
+                &self,
+                index: usize,
+                payload: &crate::Payload,
+            ) -> crate::InvokeResult {
+                match index {
+                    #(#async_index_arms,)*
+                    #not_found
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let on_service_change = on_service_change.map(|ident| {
+        quote! {
+            fn on_service_change(&self, dependency: &crate::Dependency) {
+                self.#ident(dependency)
+            }
+        }
+    });
+
+    let hooks = hooks.iter().map(|(_, hook)| hook);
+
     let gen = quote! {
         #item_impl
 
+        #[crate::async_trait]
+        #[allow(deprecated)]
         impl crate::Agent for #struct_ident {
             fn name(&self) -> &'static str {
-                stringify!(#struct_ident)
+                self.do_name()
+            }
+
+            fn namespace(&self) -> Option<&'static str> {
+                self.do_namespace()
+            }
+
+            fn version(&self) -> Option<&'static str> {
+                self.do_version()
+            }
+
+            fn description(&self) -> Option<&'static str> {
+                self.do_description()
             }
 
             fn operations(&self) -> Vec<crate::OperationDescriptor> {
                 vec![
-                    #( crate::OperationDescriptor { name: #op_names } ),*
+                    #(#descriptors),*
                 ]
             }
 
+            fn dependencies(&self) -> Vec<crate::Dependency> {
+                self.do_dependencies()
+            }
+
             fn inject_dependencies(&self, context: &crate::AxorContext) {
                 self.do_inject_dependencies(context);
             }
 
+            #on_service_change
+
+            #(#hooks)*
+
             fn call_operation(&self, payload: &crate::Payload) -> crate::InvokeResult {
-                // println!("Operation name : {}", payload.op_name_unchecked());
                 match payload.op_name_unchecked() {
-                    #(#match_arms,)*
-                    _ => crate::InvokeResult {
-                        operation: payload.name.to_string(),
-                        success: false,
-                        data: None,
-                    }
+                    #(#name_arms,)*
+                    #not_found
+                }
+            }
+
+            fn call_operation_at(&self, index: usize, payload: &crate::Payload) -> crate::InvokeResult {
+                match index {
+                    #(#index_arms,)*
+                    #not_found
                 }
             }
+
+            #call_operation_async
         }
     };
 
@@ -189,15 +508,168 @@ fn has_default_derive(attrs: &[Attribute]) -> bool {
     false
 }
 
-fn has_operation_attr(attrs: &[Attribute]) -> bool {
-    attrs.iter().any(|attr| attr.path().is_ident("operation"))
+fn option_tokens(value: Option<String>) -> TokenStream2 {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
+    }
+}
+
+fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident(name))
+}
+
+/// `Inject`, `InjectOptional` or `InjectAll` for injected fields
+fn inject_kind(ty: &Type) -> Option<String> {
+    if let Type::Path(TypePath { path, .. }) = ty {
+        let ident = path.segments.first()?.ident.to_string();
+        matches!(ident.as_str(), "Inject" | "InjectOptional" | "InjectAll").then_some(ident)
+    } else {
+        None
+    }
+}
+
+/// Typed parameters of an operation, `self` excluded
+fn operation_params(sig: &Signature) -> Vec<(String, Type)> {
+    sig.inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(PatType { pat, ty, .. }) => Some((pat, ty)),
+            FnArg::Receiver(_) => None,
+        })
+        .enumerate()
+        .map(|(i, (pat, ty))| {
+            let name = match &**pat {
+                Pat::Ident(pat_ident) => pat_ident.ident.to_string(),
+                // `Json(order): Json<Order>` is named after its binding
+                Pat::TupleStruct(PatTupleStruct { elems, .. }) if elems.len() == 1 => match &elems[0] {
+                    Pat::Ident(pat_ident) => pat_ident.ident.to_string(),
+                    _ => format!("arg{}", i),
+                },
+                _ => format!("arg{}", i),
+            };
+            (name, (**ty).clone())
+        })
+        .collect()
+}
+
+/// Readable type name, e.g. `Vec<String>` instead of `Vec < String >`
+fn type_name(ty: &Type) -> String {
+    quote!(#ty)
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace("& ", "&")
+}
+
+/// Returned type, unwrapping the `Ok` type of `Result` outputs
+fn output_type(output: &ReturnType) -> Option<&Type> {
+    let ReturnType::Type(_, ty) = output else {
+        return None;
+    };
+    if !is_result_type(ty) {
+        return Some(ty);
+    }
+    if let Type::Path(TypePath { path, .. }) = &**ty {
+        if let Some(PathArguments::AngleBracketed(args)) =
+            path.segments.last().map(|seg| &seg.arguments)
+        {
+            return args.args.iter().find_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            });
+        }
+    }
+    None
+}
+
+/// `input_schema` and `output_schema` expressions of an operation descriptor
+fn schemas(params: &[(String, Type)], output: Option<&Type>) -> (TokenStream2, TokenStream2) {
+    // Built at runtime, whether a parameter is an input depends on its `FromInvocation` impl
+    let input_schema = if params.is_empty() {
+        quote! { None }
+    } else {
+        let params = params.iter().map(|(name, ty)| {
+            let required = !is_option_type(ty);
+            quote! {
+                let schema = (&&&crate::Describe::<#ty>::new()).describe(schema, #name, #required);
+            }
+        });
+        quote! {{
+            #[allow(unused_imports)]
+            use crate::{DescribeBody as _, DescribeExtractor as _, DescribeInput as _};
+            let schema = crate::ParamsSchema::new();
+            #(#params)*
+            schema.input_schema()
+        }}
+    };
+    let output_schema = match output {
+        Some(ty) => quote! { Some(crate::schema_for::<#ty>()) },
+        None => quote! { None },
+    };
+    (input_schema, output_schema)
+}
+
+fn is_option_type(ty: &Type) -> bool {
+    if let Type::Path(TypePath { path, .. }) = ty {
+        path.segments
+            .last()
+            .map(|seg| seg.ident == "Option")
+            .unwrap_or(false)
+    } else {
+        false
+    }
+}
+
+const LIFECYCLE_HOOKS: &[&str] = &["on_init", "on_start", "on_stop"];
+
+/// Overrides the `Agent` hook with a call to the marked method, which may be `async`
+/// and may return `()` or a `Result<(), E>` whose error implements `IntoInvokeError`.
+fn lifecycle_hook(hook: &str, sig: &Signature) -> TokenStream2 {
+    let ident = &sig.ident;
+    let is_async = sig.asyncness.is_some();
+    let call = if is_async {
+        quote! { self.#ident().await }
+    } else {
+        quote! { self.#ident() }
+    };
+    let body = match &sig.output {
+        ReturnType::Type(_, ty) if is_result_type(ty) => {
+            quote! { #call.map_err(crate::IntoInvokeError::into_invoke_error) }
+        }
+        _ => quote! { #call; Ok(()) },
+    };
+
+    let sync_hook = format_ident!("{}", hook);
+    if is_async {
+        let async_hook = format_ident!("{}_async", hook);
+        quote! {
+            fn #sync_hook(&self) -> Result<(), crate::InvokeError> {
+                Err(crate::InvokeError::async_hook(#hook))
+            }
+
+            async fn #async_hook(&self) -> Result<(), crate::InvokeError> {
+                #body
+            }
+        }
+    } else {
+        quote! {
+            fn #sync_hook(&self) -> Result<(), crate::InvokeError> {
+                #body
+            }
+        }
+    }
 }
 
-fn is_inject_type(ty: &Type) -> bool {
+/// Matches `Result<..>`, `anyhow::Result<..>`, `std::io::Result<..>`...
+fn is_result_type(ty: &Type) -> bool {
     if let Type::Path(TypePath { path, .. }) = ty {
         path.segments
-            .first()
-            .map(|seg| seg.ident == "Inject")
+            .last()
+            .map(|seg| seg.ident == "Result")
             .unwrap_or(false)
     } else {
         false
@@ -0,0 +1,31 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, ItemTrait};
+
+/// Macro #[interface] on a trait
+/// - lets any implementor be registered as `dyn Trait` with `register_as`/`expose`
+pub fn expand_interface(input: TokenStream) -> TokenStream {
+    let item_trait = parse_macro_input!(input as ItemTrait);
+    let trait_ident = &item_trait.ident;
+
+    if !item_trait.generics.params.is_empty() {
+        return syn::Error::new_spanned(
+            &item_trait.generics,
+            "#[interface] does not support generic traits",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let gen = quote! {
+        #item_trait
+
+        impl<S: #trait_ident + Send + Sync + 'static> crate::Interface<S> for dyn #trait_ident {
+            fn upcast(service: ::std::sync::Arc<S>) -> ::std::sync::Arc<Self> {
+                service
+            }
+        }
+    };
+
+    gen.into()
+}
@@ -1,20 +1,50 @@
 extern crate proc_macro;
 
 mod agent_macro;
+mod interface_macro;
 mod operation_macro;
 
 use proc_macro::TokenStream;
 
 #[proc_macro_attribute]
-pub fn agent(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    agent_macro::mark_agent_struct(item)
+pub fn agent(attr: TokenStream, item: TokenStream) -> TokenStream {
+    agent_macro::mark_agent_struct(attr, item)
 }
 
 #[proc_macro_attribute]
-pub fn agent_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    agent_macro::expand_agent_impl(item)
+pub fn agent_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
+    agent_macro::expand_agent_impl(attr, item)
 }
 #[proc_macro_attribute]
 pub fn operation(_attr: TokenStream, item: TokenStream) -> TokenStream {
     operation_macro::mark_operation(item)
 }
+
+/// Marks the `fn(&self, &Dependency)` method notified when an injected service is replaced
+#[proc_macro_attribute]
+pub fn on_service_change(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+/// Marks the method run by `AxorContext::start` once every agent is injected
+#[proc_macro_attribute]
+pub fn on_init(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+/// Marks the method run by `AxorContext::start` once every agent is initialized
+#[proc_macro_attribute]
+pub fn on_start(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+/// Marks the method run by `AxorContext::stop`
+#[proc_macro_attribute]
+pub fn on_stop(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+#[proc_macro_attribute]
+pub fn interface(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    interface_macro::expand_interface(item)
+}
@@ -1,5 +1,95 @@
 use proc_macro::TokenStream;
+use syn::{ext::IdentExt, Attribute, Ident, LitStr, Meta, Token};
 
 pub fn mark_operation(item: TokenStream) -> TokenStream {
     item
 }
+
+/// Arguments of `#[operation(name = "...", description = "...", deprecated, tags(...), hidden)]`
+#[derive(Default)]
+pub struct OperationArgs {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub deprecated: bool,
+    pub tags: Vec<String>,
+    pub hidden: bool,
+}
+
+/// Reads the `#[operation]` attribute of a method, `None` if the method is not an operation
+pub fn parse_operation_attr(attrs: &[Attribute]) -> syn::Result<Option<OperationArgs>> {
+    let Some(attr) = attrs.iter().find(|attr| attr.path().is_ident("operation")) else {
+        return Ok(None);
+    };
+    let mut args = OperationArgs::default();
+    if !matches!(attr.meta, Meta::List(_)) {
+        return Ok(Some(args));
+    }
+
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("name") {
+            let lit: LitStr = meta.value()?.parse()?;
+            // `.` separates the agent address from the operation, `@` introduces the version
+            let forbidden = ['.', '@'];
+            if lit.value().is_empty() || lit.value().contains(forbidden) {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    format!("must be non-empty and must not contain {:?}", forbidden),
+                ));
+            }
+            args.name = Some(lit.value());
+        } else if meta.path.is_ident("description") {
+            args.description = Some(meta.value()?.parse::<LitStr>()?.value());
+        } else if meta.path.is_ident("deprecated") {
+            args.deprecated = true;
+        } else if meta.path.is_ident("hidden") {
+            args.hidden = true;
+        } else if meta.path.is_ident("tags") {
+            // tags("admin", "users") or tags(admin, users)
+            let content;
+            syn::parenthesized!(content in meta.input);
+            while !content.is_empty() {
+                if content.peek(LitStr) {
+                    args.tags.push(content.parse::<LitStr>()?.value());
+                } else {
+                    args.tags.push(Ident::parse_any(&content)?.to_string());
+                }
+                if !content.is_empty() {
+                    content.parse::<Token![,]>()?;
+                }
+            }
+        } else {
+            return Err(meta.error(
+                "unsupported operation argument, expected `name`, `description`, `deprecated`, `tags` or `hidden`",
+            ));
+        }
+        Ok(())
+    })?;
+
+    Ok(Some(args))
+}
+
+/// Rustdoc comment of an item, lines joined with `\n`
+pub fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(nv) => match &nv.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(lit),
+                    ..
+                }) => Some(lit.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).trim_end().to_string())
+        .collect();
+
+    let doc = lines.join("\n").trim().to_string();
+    if doc.is_empty() {
+        None
+    } else {
+        Some(doc)
+    }
+}
@@ -0,0 +1,73 @@
+//! Input decoding cost of a large document.
+//!
+//! Run with `cargo bench -p axor --bench decode`: `from_slice` payloads decode their
+//! inputs straight from the received JSON, `value` payloads from an already parsed tree.
+
+use axor::prelude::*;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Serialize, Deserialize)]
+struct Row {
+    id: u64,
+    label: String,
+    tags: Vec<String>,
+}
+
+#[agent]
+struct RowAgent;
+
+#[agent_impl]
+impl RowAgent {
+    #[operation]
+    fn count(&self, rows: Vec<Row>) -> usize {
+        rows.len()
+    }
+
+    #[operation]
+    fn offset(&self, rows: Vec<Row>, offset: u64) -> u64 {
+        rows.iter().map(|row| row.id + offset).sum()
+    }
+}
+
+fn rows(count: u64) -> serde_json::Value {
+    (0..count)
+        .map(|id| json!({ "id": id, "label": format!("row {}", id), "tags": ["a", "b", "c"] }))
+        .collect()
+}
+
+fn decode(c: &mut Criterion) {
+    let context = AxorContext::new();
+    context.register(RowAgent);
+    context.init();
+    // Dispatched on the agent, so payloads are borrowed rather than cloned on each iteration
+    let agent = context.resolve::<RowAgent>();
+
+    let mut group = c.benchmark_group("decode");
+    for count in [100, 10_000] {
+        let single = json!({ "name": "RowAgent.count", "data": rows(count), "success": false });
+        let named = json!({
+            "name": "RowAgent.offset",
+            "data": { "rows": rows(count), "offset": 1 },
+            "success": false
+        });
+        for (op, json) in [("single", single), ("named", named)] {
+            let bytes = serde_json::to_vec(&json).unwrap();
+
+            let payload: Payload = serde_json::from_slice(&bytes).unwrap();
+            group.bench_with_input(BenchmarkId::new(format!("{}/value", op), count), &payload, |b, payload| {
+                b.iter(|| agent.call_operation(black_box(payload)))
+            });
+
+            let payload = Payload::from_slice(&bytes).unwrap();
+            group.bench_with_input(BenchmarkId::new(format!("{}/from_slice", op), count), &payload, |b, payload| {
+                b.iter(|| agent.call_operation(black_box(payload)))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, decode);
+criterion_main!(benches);
@@ -0,0 +1,84 @@
+//! Dynamic invocation cost with a growing number of agents and operations.
+//!
+//! Run with `cargo bench -p axor --bench invoke`: timings should stay flat
+//! from 1 to 80 agents, since routing goes through the context index.
+
+use axor::{Agent, AxorContext, InvokeError, InvokeResult, OperationDescriptor, Payload};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const OPERATIONS: [&str; 10] = [
+    "op0", "op1", "op2", "op3", "op4", "op5", "op6", "op7", "op8", "op9",
+];
+
+/// One distinct agent type per `N`, each exposing ten operations
+struct BenchAgent<const N: usize> {
+    name: &'static str,
+}
+
+impl<const N: usize> BenchAgent<N> {
+    fn new() -> Self {
+        Self {
+            name: Box::leak(format!("Agent{}", N).into_boxed_str()),
+        }
+    }
+}
+
+impl<const N: usize> Agent for BenchAgent<N> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn operations(&self) -> Vec<OperationDescriptor> {
+        OPERATIONS.iter().map(|op| OperationDescriptor::new(op)).collect()
+    }
+
+    fn inject_dependencies(&self, _context: &AxorContext) {}
+
+    fn call_operation(&self, payload: &Payload) -> InvokeResult {
+        match OPERATIONS.iter().position(|op| *op == payload.op_name_unchecked()) {
+            Some(index) => self.call_operation_at(index, payload),
+            None => InvokeResult::err(
+                payload.name.to_string(),
+                InvokeError::operation_not_found(&payload.name),
+            ),
+        }
+    }
+
+    fn call_operation_at(&self, index: usize, _payload: &Payload) -> InvokeResult {
+        InvokeResult::ok(OPERATIONS[index], None)
+    }
+}
+
+macro_rules! register_agents {
+    ($context:expr, $count:expr, [$($n:literal)*]) => {
+        $( if $n < $count { $context.register(BenchAgent::<$n>::new()); } )*
+    };
+}
+
+fn context(count: usize) -> AxorContext {
+    let context = AxorContext::new();
+    register_agents!(context, count, [
+        0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19
+        20 21 22 23 24 25 26 27 28 29 30 31 32 33 34 35 36 37 38 39
+        40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59
+        60 61 62 63 64 65 66 67 68 69 70 71 72 73 74 75 76 77 78 79
+    ]);
+    context.init();
+    context
+}
+
+fn invoke(c: &mut Criterion) {
+    let mut group = c.benchmark_group("invoke");
+    for count in [1, 10, 80] {
+        let context = context(count);
+        // Last registered agent, last operation: worst case for a linear scan
+        let payload = Payload::new(format!("Agent{}.op9", count - 1));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &payload, |b, payload| {
+            b.iter(|| context.invoke(black_box(payload.clone())))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, invoke);
+criterion_main!(benches);
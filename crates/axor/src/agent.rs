@@ -1,25 +1,152 @@
-use crate::{operation::OperationDescriptor, AxorContext, Payload};
+use crate::{operation::OperationDescriptor, AxorContext, Dependency, InvokeError, Payload};
+use async_trait::async_trait;
 use downcast_rs::{impl_downcast, DowncastSync};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+#[async_trait]
 pub trait Agent: DowncastSync + Send + Sync {
 
     fn name(&self) -> &'static str;
 
+    /// Optional dot-separated namespace, prefixed to the name in payload addresses.
+    fn namespace(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Optional version, several versions of an agent can be registered side by side.
+    fn version(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        None
+    }
+
     fn operations(&self) -> Vec<OperationDescriptor>;
 
+    /// Dependencies filled by `inject_dependencies`, checked when building a context.
+    fn dependencies(&self) -> Vec<Dependency> {
+        Vec::new()
+    }
+
     fn inject_dependencies(&self, context: &AxorContext);
 
+    /// Called after [`AxorContext::replace_service`] swapped a service this agent depends on.
+    fn on_service_change(&self, dependency: &Dependency) {
+        let _ = dependency;
+    }
+
+    /// Called by [`AxorContext::start`] once every agent is injected, dependencies first.
+    fn on_init(&self) -> Result<(), InvokeError> {
+        Ok(())
+    }
+
+    /// Called by [`AxorContext::start`] once every agent is initialized, dependencies first.
+    fn on_start(&self) -> Result<(), InvokeError> {
+        Ok(())
+    }
+
+    /// Called by [`AxorContext::stop`], dependents first.
+    fn on_stop(&self) -> Result<(), InvokeError> {
+        Ok(())
+    }
+
+    /// Async counterpart of [`on_init`](Self::on_init), used by `AxorContext::start_async`.
+    async fn on_init_async(&self) -> Result<(), InvokeError> {
+        self.on_init()
+    }
+
+    /// Async counterpart of [`on_start`](Self::on_start), used by `AxorContext::start_async`.
+    async fn on_start_async(&self) -> Result<(), InvokeError> {
+        self.on_start()
+    }
+
+    /// Async counterpart of [`on_stop`](Self::on_stop), used by `AxorContext::stop_async`.
+    async fn on_stop_async(&self) -> Result<(), InvokeError> {
+        self.on_stop()
+    }
+
     fn call_operation(&self, payload: &Payload) -> InvokeResult;
 
+    /// Async dispatch path, used by `AxorContext::invoke_async`.
+    /// Agents without async operations can rely on the default, which runs `call_operation`.
+    async fn call_operation_async(&self, payload: &Payload) -> InvokeResult {
+        self.call_operation(payload)
+    }
+
+    /// Calls the operation at `index` in [`operations`](Self::operations), skipping the name lookup.
+    /// Used by the context routing index, defaults to `call_operation`.
+    fn call_operation_at(&self, index: usize, payload: &Payload) -> InvokeResult {
+        let _ = index;
+        self.call_operation(payload)
+    }
+
+    /// Async counterpart of [`call_operation_at`](Self::call_operation_at), defaults to it.
+    /// Agents with async operations override it along with `call_operation_async`.
+    async fn call_operation_async_at(&self, index: usize, payload: &Payload) -> InvokeResult {
+        self.call_operation_at(index, payload)
+    }
+
+}
+
+/// Address of an agent in payload names: `[namespace.]name[@version]`.
+pub fn agent_route(agent: &dyn Agent) -> String {
+    let mut route = match agent.namespace() {
+        Some(namespace) => format!("{}.{}", namespace, agent.name()),
+        None => agent.name().to_string(),
+    };
+    if let Some(version) = agent.version() {
+        route.push('@');
+        route.push_str(version);
+    }
+    route
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InvokeResult {
+    /// Correlation id of the invoked [`Payload`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     pub operation: String,
     pub success: bool,
     pub data: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<InvokeError>,
+}
+
+impl InvokeResult {
+    pub fn ok(operation: impl Into<String>, data: Option<Value>) -> Self {
+        Self {
+            id: None,
+            operation: operation.into(),
+            success: true,
+            data,
+            error: None,
+        }
+    }
+
+    pub fn err(operation: impl Into<String>, error: InvokeError) -> Self {
+        Self {
+            id: None,
+            operation: operation.into(),
+            success: false,
+            data: None,
+            error: Some(error),
+        }
+    }
+
+    /// Serializes an operation output, reporting serialization failures as `internal` errors.
+    pub fn from_output<T: Serialize>(operation: impl Into<String>, output: &T) -> Self {
+        let operation = operation.into();
+        match serde_json::to_value(output) {
+            Ok(value) => Self::ok(operation, Some(value)),
+            Err(e) => Self::err(
+                operation,
+                InvokeError::internal(format!("Output serialization failed: {}", e)),
+            ),
+        }
+    }
 }
 
 impl_downcast!(sync Agent);
@@ -0,0 +1,69 @@
+use std::num::NonZeroUsize;
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{InvokeError, InvokeResult, Payload};
+
+/// How the payloads of [`AxorContext::invoke_batch`](crate::AxorContext::invoke_batch) are run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMode {
+    /// One after the other, in order.
+    #[default]
+    Sequential,
+    /// Concurrently: on threads with `invoke_batch`, as concurrent futures with `invoke_batch_async`.
+    Parallel,
+}
+
+/// Options of [`AxorContext::invoke_batch`](crate::AxorContext::invoke_batch).
+///
+/// Deserializable, so runtimes can forward them along with the payloads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchOptions {
+    #[serde(default)]
+    pub mode: BatchMode,
+    /// Once an item fails, the items not started yet fail with [`ErrorCode::Skipped`](crate::ErrorCode::Skipped).
+    /// In parallel mode, items already running are not interrupted.
+    #[serde(default)]
+    pub stop_on_failure: bool,
+    /// Maximum number of items running at once in parallel mode, defaults to the available parallelism.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<usize>,
+}
+
+impl BatchOptions {
+    pub fn sequential() -> Self {
+        Self::default()
+    }
+
+    pub fn parallel() -> Self {
+        Self {
+            mode: BatchMode::Parallel,
+            ..Self::default()
+        }
+    }
+
+    pub fn stop_on_failure(mut self) -> Self {
+        self.stop_on_failure = true;
+        self
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = Some(concurrency);
+        self
+    }
+
+    /// Items run at once in parallel mode, at least one.
+    pub(crate) fn concurrency(&self) -> usize {
+        self.concurrency
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, NonZeroUsize::get))
+            .max(1)
+    }
+}
+
+pub(crate) fn skipped(payload: Payload) -> InvokeResult {
+    let mut result = InvokeResult::err(payload.name, InvokeError::skipped());
+    result.id = payload.id;
+    result
+}
@@ -0,0 +1,179 @@
+use std::any::{type_name, TypeId};
+use std::fmt;
+use std::sync::Arc;
+
+use crate::middleware::MiddlewareScope;
+use crate::registry::Registry;
+use crate::service::{erase, Interface, ServiceEntry, ServiceFactory};
+use crate::validation::validate;
+use crate::{Agent, AxorContext, Middleware, ServiceLifetime, ValidationError};
+
+/// Collects agents and services, then builds a ready-to-serve [`AxorContext`].
+///
+/// ```ignore
+/// let context = AxorContext::builder()
+///     .agent(HelloAgent::default())
+///     .service(ConsoleLogger)
+///     .build()?;
+/// ```
+#[derive(Default)]
+pub struct AxorContextBuilder {
+    registry: Registry,
+    /// First registration error, reported by `build`
+    error: Option<BuildError>,
+}
+
+impl AxorContextBuilder {
+    pub fn agent<T: Agent + 'static>(mut self, agent: T) -> Self {
+        self.registry.add_agent(Arc::new(agent));
+        self
+    }
+
+    pub fn service<T: Send + Sync + 'static>(mut self, service: T) -> Self {
+        self.registry.add_service(Arc::new(service));
+        self
+    }
+
+    /// See [`AxorContext::register_as`].
+    pub fn service_as<I, S>(mut self, service: S) -> Self
+    where
+        I: ?Sized + Interface<S>,
+        S: Send + Sync + 'static,
+    {
+        self.registry.add_service(I::upcast(Arc::new(service)));
+        self
+    }
+
+    /// See [`AxorContext::expose`].
+    pub fn expose<I, S>(mut self) -> Self
+    where
+        I: ?Sized + Interface<S>,
+        S: Send + Sync + 'static,
+    {
+        if !self.registry.add_interface::<I, S>() && self.error.is_none() {
+            self.error = Some(BuildError::NotExposable {
+                service: type_name::<S>(),
+                interface: type_name::<I>(),
+            });
+        }
+        self
+    }
+
+    /// See [`AxorContext::register_named_service`].
+    pub fn named_service<T: Send + Sync + 'static>(mut self, name: &str, service: T) -> Self {
+        self.registry.add_entry(
+            TypeId::of::<T>(),
+            Some(name),
+            ServiceEntry::Instance(erase(Arc::new(service))),
+        );
+        self
+    }
+
+    /// See [`AxorContext::register_named_as`].
+    pub fn named_service_as<I, S>(mut self, name: &str, service: S) -> Self
+    where
+        I: ?Sized + Interface<S>,
+        S: Send + Sync + 'static,
+    {
+        self.registry.add_entry(
+            TypeId::of::<I>(),
+            Some(name),
+            ServiceEntry::Instance(erase(I::upcast(Arc::new(service)))),
+        );
+        self
+    }
+
+    /// See [`AxorContext::register_into`].
+    pub fn service_into<T: ?Sized + Send + Sync + 'static>(mut self, service: Arc<T>) -> Self {
+        self.registry.add_into(TypeId::of::<T>(), erase(service));
+        self
+    }
+
+    /// See [`AxorContext::register_factory`].
+    pub fn factory<T, F>(mut self, lifetime: ServiceLifetime, factory: F) -> Self
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&AxorContext) -> T + Send + Sync + 'static,
+    {
+        let factory = ServiceFactory::new(lifetime, move |context| Arc::new(factory(context)));
+        self.registry
+            .add_factory(TypeId::of::<T>(), Arc::new(factory));
+        self
+    }
+
+    /// See [`AxorContext::register_middleware`].
+    pub fn middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.registry
+            .add_middleware(MiddlewareScope::Global, Arc::new(middleware));
+        self
+    }
+
+    /// See [`AxorContext::register_agent_middleware`].
+    pub fn agent_middleware<M: Middleware + 'static>(mut self, address: &str, middleware: M) -> Self {
+        self.registry.add_middleware(
+            MiddlewareScope::Agent(address.to_string()),
+            Arc::new(middleware),
+        );
+        self
+    }
+
+    /// See [`AxorContext::register_operation_middleware`].
+    pub fn operation_middleware<M: Middleware + 'static>(mut self, name: &str, middleware: M) -> Self {
+        self.registry.add_middleware(
+            MiddlewareScope::Operation(name.to_string()),
+            Arc::new(middleware),
+        );
+        self
+    }
+
+    /// Validates the registrations and the dependency graph, injects every agent and returns the context.
+    pub fn build(mut self) -> Result<AxorContext, BuildError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        self.registry.rebuild_routes();
+        if let Some(route) = self.registry.routes().duplicates().first() {
+            return Err(BuildError::DuplicateRoute {
+                route: route.clone(),
+            });
+        }
+
+        validate(&self.registry, None).map_err(BuildError::InvalidDependencies)?;
+
+        let context = AxorContext::from_registry(self.registry);
+        for agent in context.registry().agents() {
+            agent.inject_dependencies(&context);
+        }
+        Ok(context)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuildError {
+    /// Two agents are addressed by the same `[namespace.]name[@version]`.
+    DuplicateRoute { route: String },
+    /// Missing registrations or injection cycles.
+    InvalidDependencies(ValidationError),
+    /// `expose` was called before the service was registered.
+    NotExposable {
+        service: &'static str,
+        interface: &'static str,
+    },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::DuplicateRoute { route } => {
+                write!(f, "Several agents are registered as `{}`", route)
+            }
+            BuildError::InvalidDependencies(error) => error.fmt(f),
+            BuildError::NotExposable { service, interface } => {
+                write!(f, "Cannot expose `{}` as `{}`: register it first", service, interface)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
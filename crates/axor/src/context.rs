@@ -1,111 +1,747 @@
+use arc_swap::{ArcSwap, Guard};
+use futures::stream::{self, StreamExt};
 use serde::Serialize;
 
-use crate::{Agent, InvokeResult, Payload};
-use std::any::{Any, TypeId};
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use crate::batch;
+use crate::invocation::Invoking;
+use crate::middleware::{Chain, MiddlewareScope};
+use crate::registry::Registry;
+use crate::routing::Route;
+use crate::service::{
+    erase, unerase, Interface, Scope, Scoped, ServiceEntry, ServiceFactory, ServiceSlot,
+};
+use crate::validation::{dependency_order, validate};
+use crate::{
+    agent_route, Agent, AxorContextBuilder, BatchMode, BatchOptions, Dependency, DependencyKind,
+    ErrorCode, InvokeContext, InvokeError, InvokeResult, LifecycleError, LifecycleHook,
+    Middleware, OperationDescriptor, Payload, ServiceLifetime, ValidationError,
+};
+use std::any::{type_name, Any, TypeId};
+use std::fmt;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
 
+/// Registry of agents and services.
+///
+/// Cloning is cheap and yields a handle on the same context. Lookups and invocations
+/// read an immutable snapshot of the registry and take no locks; registrations publish
+/// a new snapshot.
+#[derive(Clone)]
 pub struct AxorContext {
-    agents: RwLock<HashMap<TypeId, Arc<dyn Agent>>>,
-    services: RwLock<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+    inner: Arc<ContextInner>,
+}
+
+struct ContextInner {
+    registry: ArcSwap<Registry>,
+    /// Context consulted for whatever this one does not register, see [`AxorContext::child`]
+    parent: Option<AxorContext>,
+}
+
+#[derive(Clone)]
+pub(crate) struct WeakContext(Weak<ContextInner>);
+
+impl WeakContext {
+    pub fn upgrade(&self) -> Option<AxorContext> {
+        self.0.upgrade().map(|inner| AxorContext { inner })
+    }
+}
+
+impl Default for AxorContext {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AxorContext {
     pub fn new() -> Self {
+        Self::from_registry(Registry::default())
+    }
+
+    /// Starts a context whose agents are injected and validated by [`AxorContextBuilder::build`].
+    pub fn builder() -> AxorContextBuilder {
+        AxorContextBuilder::default()
+    }
+
+    pub(crate) fn from_registry(registry: Registry) -> Self {
         Self {
-            agents: RwLock::new(HashMap::new()),
-            services: RwLock::new(HashMap::new()),
+            inner: Arc::new(ContextInner {
+                registry: ArcSwap::from_pointee(registry),
+                parent: None,
+            }),
         }
     }
 
+    /// Creates an empty context inheriting every agent and service of this one.
+    ///
+    /// Registrations in the child override the parent's; lookups, invocations and the manifest
+    /// fall back to the parent for anything the child does not register. Inherited agents keep
+    /// the dependencies injected by the parent: register an agent in the child to have it
+    /// injected with the child's overrides. Likewise, inherited factories are called with the
+    /// parent and only see its registrations.
+    ///
+    /// ```ignore
+    /// let tenant = context.child();
+    /// tenant.register_as::<dyn Mailer, _>(TenantMailer::new("acme"));
+    /// tenant.register(NotificationAgent::default());
+    /// tenant.init();
+    /// ```
+    pub fn child(&self) -> AxorContext {
+        Self {
+            inner: Arc::new(ContextInner {
+                registry: ArcSwap::from_pointee(Registry::default()),
+                parent: Some(self.clone()),
+            }),
+        }
+    }
+
+    pub fn parent(&self) -> Option<&AxorContext> {
+        self.inner.parent.as_ref()
+    }
+
+    /// Handle that does not keep the context alive, held by injected fields.
+    pub(crate) fn downgrade(&self) -> WeakContext {
+        WeakContext(Arc::downgrade(&self.inner))
+    }
+
+    pub(crate) fn registry(&self) -> Guard<Arc<Registry>> {
+        self.inner.registry.load()
+    }
+
+    /// Publishes a modified copy of the registry.
+    fn update(&self, mut f: impl FnMut(&mut Registry)) {
+        self.inner.registry.rcu(|current| {
+            let mut next = Registry::clone(current);
+            f(&mut next);
+            next
+        });
+    }
+
+    /// Registers an agent, replacing any agent of the same type.
+    ///
+    /// Panics if an agent of another type is already routed at the same address,
+    /// see [`try_register`](Self::try_register).
     pub fn register<T: Agent + 'static>(&self, agent: T) {
-        let agent_arc: Arc<T> = Arc::new(agent);
-        let agent_dyn: Arc<dyn Agent> = agent_arc.clone();
-        let service_dyn: Arc<dyn Any + Send + Sync> = agent_arc;
+        if let Err(error) = self.try_register(agent) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Registers an agent, replacing any agent of the same type, unless an agent of another
+    /// type is already routed at the same `[namespace.]name[@version]`.
+    pub fn try_register<T: Agent + 'static>(&self, agent: T) -> Result<(), RegisterError> {
+        let route = agent_route(&agent);
+        let agent = Arc::new(agent);
+        let mut duplicate = None;
+        self.update(|registry| {
+            duplicate = registry.agent_routed_as(&route, TypeId::of::<T>());
+            if duplicate.is_none() {
+                registry.add_agent(agent.clone());
+                registry.rebuild_routes();
+            }
+        });
+        match duplicate {
+            Some(_) => Err(RegisterError::DuplicateRoute { route }),
+            None => Ok(()),
+        }
+    }
+
+    /// Removes the agent of type `T`, which stops being routed and resolvable.
+    pub fn unregister<T: Agent + 'static>(&self) -> Option<Arc<T>> {
+        let mut removed = None;
+        self.update(|registry| {
+            removed = registry.remove_agent(TypeId::of::<T>());
+            registry.rebuild_routes();
+        });
+        removed?.downcast_arc::<T>().ok()
+    }
+
+    /// Stops routing invocations to the agents addressed by `address` (`[namespace.]name[@version]`,
+    /// every version without one), which then fail with [`ErrorCode::AgentDisabled`].
+    ///
+    /// Disabled agents stay registered and resolvable. Returns `false` if no agent matches.
+    pub fn disable(&self, address: &str) -> bool {
+        self.set_enabled(address, false)
+    }
+
+    /// Routes invocations to agents disabled with [`disable`](Self::disable) again.
+    pub fn enable(&self, address: &str) -> bool {
+        self.set_enabled(address, true)
+    }
 
-        self.agents
-            .write()
-            .unwrap()
-            .insert(TypeId::of::<T>(), agent_dyn);
-        self.services
-            .write()
-            .unwrap()
-            .insert(TypeId::of::<T>(), service_dyn);
+    fn set_enabled(&self, address: &str, enabled: bool) -> bool {
+        let mut found = false;
+        self.update(|registry| {
+            found = registry.set_enabled(address, enabled);
+            registry.rebuild_routes();
+        });
+        found
     }
 
     pub fn get<T: Agent + 'static>(&self) -> Option<Arc<T>> {
-        let map = self.agents.read().unwrap();
-        map.get(&TypeId::of::<T>())
-            .and_then(|agent| agent.clone().downcast_arc::<T>().ok())
+        let agent = self.registry().agent(TypeId::of::<T>()).cloned();
+        match agent {
+            Some(agent) => agent.downcast_arc::<T>().ok(),
+            None => self.parent()?.get::<T>(),
+        }
     }
 
     pub fn register_service<T: Send + Sync + 'static>(&self, service: T) {
-        let mut map = self.services.write().unwrap();
-        map.insert(TypeId::of::<T>(), Arc::new(service));
+        let service = Arc::new(service);
+        self.update(|registry| registry.add_service(service.clone()));
     }
 
-    pub fn get_service<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
-        let map = self.services.read().unwrap();
-        map.get(&TypeId::of::<T>())?.clone().downcast::<T>().ok()
+    /// Registers `service` as the trait object `I`, resolved as `Arc<I>` and injected into `Inject<I>`.
+    ///
+    /// ```ignore
+    /// context.register_as::<dyn Logger, _>(ConsoleLogger);
+    /// let logger: Arc<dyn Logger> = context.resolve::<dyn Logger>();
+    /// ```
+    pub fn register_as<I, S>(&self, service: S)
+    where
+        I: ?Sized + Interface<S>,
+        S: Send + Sync + 'static,
+    {
+        let service = I::upcast(Arc::new(service));
+        self.update(|registry| registry.add_service(service.clone()));
     }
 
-    pub fn resolve<T: Send + Sync + 'static>(&self) -> Arc<T> {
-        let map = self.services.read().unwrap();
-        let service = map
-            .get(&TypeId::of::<T>())
-            .expect("Service not found")
-            .clone();
+    /// Exposes the service already registered as `S` under the interface `I` as well.
+    ///
+    /// Both resolve to the same instance, so one service can back several traits, and
+    /// [`replace_service`](Self::replace_service) on `S` reaches every interface it is exposed as:
+    ///
+    /// ```ignore
+    /// context.register_service(FileStore::open(path));
+    /// context.expose::<dyn Reader, FileStore>();
+    /// context.expose::<dyn Writer, FileStore>();
+    /// ```
+    pub fn expose<I, S>(&self)
+    where
+        I: ?Sized + Interface<S>,
+        S: Send + Sync + 'static,
+    {
+        let mut exposed = true;
+        self.update(|registry| exposed = registry.add_interface::<I, S>());
+        if !exposed {
+            not_exposable::<I, S>();
+        }
+    }
 
-        downcast_arc::<T>(service).expect("Type mismatch when downcasting service")
+    /// Registers a factory building `T` on demand, as often as `lifetime` requires.
+    ///
+    /// ```ignore
+    /// context.register_factory(ServiceLifetime::Scoped, |ctx| Transaction::begin(&ctx.resolve::<Pool>()));
+    /// ```
+    pub fn register_factory<T, F>(&self, lifetime: ServiceLifetime, factory: F)
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&AxorContext) -> T + Send + Sync + 'static,
+    {
+        let factory = ServiceFactory::new(lifetime, move |context| Arc::new(factory(context)));
+        let factory = Arc::new(factory);
+        self.update(|registry| registry.add_factory(TypeId::of::<T>(), factory.clone()));
     }
 
-    pub fn init(&self) {
-        let agents = self.agents.read().unwrap();
-        for agent in agents.values() {
+    /// Registers `service` under `name`, next to the unnamed service of the same type.
+    ///
+    /// ```ignore
+    /// context.register_named_service("replica", ReplicaConfig::load());
+    /// ```
+    pub fn register_named_service<T: Send + Sync + 'static>(&self, name: &str, service: T) {
+        let service = ServiceEntry::Instance(erase(Arc::new(service)));
+        self.update(|registry| registry.add_entry(TypeId::of::<T>(), Some(name), service.clone()));
+    }
+
+    /// [`register_as`](Self::register_as) under `name`, next to the unnamed service of the same interface.
+    ///
+    /// ```ignore
+    /// context.register_named_as::<dyn Database, _>("replica", Replica::connect());
+    /// ```
+    pub fn register_named_as<I, S>(&self, name: &str, service: S)
+    where
+        I: ?Sized + Interface<S>,
+        S: Send + Sync + 'static,
+    {
+        let service = ServiceEntry::Instance(erase(I::upcast(Arc::new(service))));
+        self.update(|registry| registry.add_entry(TypeId::of::<I>(), Some(name), service.clone()));
+    }
+
+    /// Adds `service` to the services collected for `T`, which is usually a trait object.
+    ///
+    /// ```ignore
+    /// context.register_into::<dyn Validator>(Arc::new(NotEmpty));
+    /// context.register_into::<dyn Validator>(Arc::new(MaxLength(64)));
+    /// ```
+    pub fn register_into<T: ?Sized + Send + Sync + 'static>(&self, service: Arc<T>) {
+        let service = erase(service);
+        self.update(|registry| registry.add_into(TypeId::of::<T>(), service.clone()));
+    }
+
+    /// Every service registered with [`register_into`](Self::register_into), in registration order,
+    /// the parent's first.
+    pub fn get_all<T: ?Sized + Send + Sync + 'static>(&self) -> Vec<Arc<T>> {
+        let mut services = self.parent().map(Self::get_all::<T>).unwrap_or_default();
+        services.extend(
+            self.registry()
+                .collection(TypeId::of::<T>())
+                .iter()
+                .map(unerase::<T>),
+        );
+        services
+    }
+
+    pub fn get_service<T: ?Sized + Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.lookup::<T>(None)
+    }
+
+    pub fn get_named_service<T: ?Sized + Send + Sync + 'static>(&self, name: &str) -> Option<Arc<T>> {
+        self.lookup::<T>(Some(name))
+    }
+
+    pub fn resolve<T: ?Sized + Send + Sync + 'static>(&self) -> Arc<T> {
+        self.lookup::<T>(None).unwrap_or_else(|| {
+            panic!(
+                "Service not found: `{}` is not registered, call `register` or `register_service` first",
+                type_name::<T>()
+            )
+        })
+    }
+
+    pub fn resolve_named<T: ?Sized + Send + Sync + 'static>(&self, name: &str) -> Arc<T> {
+        self.lookup::<T>(Some(name)).unwrap_or_else(|| {
+            panic!(
+                "Service not found: no `{}` registered as \"{}\" (registered names: {:?})",
+                type_name::<T>(),
+                name,
+                self.service_names(TypeId::of::<T>())
+            )
+        })
+    }
+
+    fn lookup<T: ?Sized + Send + Sync + 'static>(&self, name: Option<&str>) -> Option<Arc<T>> {
+        let (slot, owner) = self.service_slot(TypeId::of::<T>(), name)?;
+        let entry = Arc::clone(&slot.entry());
+        Some(entry.resolve::<T>(&owner))
+    }
+
+    /// Service registered here, or in the closest ancestor registering it, with the context
+    /// registering it.
+    ///
+    /// Factories are called with that context, so the overrides of a child never end up in
+    /// the singletons of its parent.
+    pub(crate) fn service_slot(
+        &self,
+        type_id: TypeId,
+        name: Option<&str>,
+    ) -> Option<(Arc<ServiceSlot>, AxorContext)> {
+        match self.registry().service(type_id, name) {
+            Some(slot) => Some((slot.clone(), self.clone())),
+            None => self.parent()?.service_slot(type_id, name),
+        }
+    }
+
+    /// Swaps the service registered as `T` in place.
+    ///
+    /// Every `Inject<T>` field sees the new service on its next `resolve`, and agents depending
+    /// on `T` are notified through their `#[on_service_change]` method. Registers `service` when
+    /// this context has no `T` yet, overriding the parent's one.
+    ///
+    /// ```ignore
+    /// context.replace_service(Config::load(path)?);
+    /// ```
+    pub fn replace_service<T: Send + Sync + 'static>(&self, service: T) {
+        let entry = ServiceEntry::Instance(erase(Arc::new(service)));
+        self.replace_entry(TypeId::of::<T>(), None, entry);
+    }
+
+    /// [`replace_service`](Self::replace_service) for a service registered with [`register_as`](Self::register_as).
+    pub fn replace_as<I, S>(&self, service: S)
+    where
+        I: ?Sized + Interface<S>,
+        S: Send + Sync + 'static,
+    {
+        let entry = ServiceEntry::Instance(erase(I::upcast(Arc::new(service))));
+        self.replace_entry(TypeId::of::<I>(), None, entry);
+    }
+
+    /// [`replace_service`](Self::replace_service) for a named service.
+    pub fn replace_named_service<T: Send + Sync + 'static>(&self, name: &str, service: T) {
+        let entry = ServiceEntry::Instance(erase(Arc::new(service)));
+        self.replace_entry(TypeId::of::<T>(), Some(name), entry);
+    }
+
+    fn replace_entry(&self, type_id: TypeId, name: Option<&str>, entry: ServiceEntry) {
+        let slot = self.registry().service(type_id, name).cloned();
+        let added = slot.is_none();
+        match slot {
+            Some(slot) => slot.replace(entry),
+            None => self.update(|registry| registry.add_entry(type_id, name, entry.clone())),
+        }
+
+        // Interfaces exposing the service read through its registration, and change with it
+        let registry = self.registry();
+        let mut changed = vec![type_id];
+        if name.is_none() {
+            changed.extend_from_slice(registry.exposed_as(type_id));
+        }
+        for agent in registry.agents() {
+            let dependencies: Vec<_> = agent
+                .dependencies()
+                .into_iter()
+                .filter(|dependency| dependency.kind != DependencyKind::All)
+                .filter(|dependency| changed.contains(&dependency.type_id) && dependency.name == name)
+                .collect();
+            // Dependents were injected from the parent's registration, move them to the new one
+            if added && !dependencies.is_empty() {
+                agent.inject_dependencies(self);
+            }
+            for dependency in &dependencies {
+                agent.on_service_change(dependency);
+            }
+        }
+    }
+
+    fn service_names(&self, type_id: TypeId) -> Vec<String> {
+        let mut names: Vec<_> = self.parent().map(|parent| parent.service_names(type_id)).unwrap_or_default();
+        names.extend(self.registry().service_names(type_id).into_iter().map(str::to_string));
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// Wraps every invocation in `middleware`, after the middlewares registered before it.
+    pub fn register_middleware<M: Middleware + 'static>(&self, middleware: M) {
+        self.add_middleware(MiddlewareScope::Global, Arc::new(middleware));
+    }
+
+    /// Wraps the invocations of the agent at `address`, `[namespace.]name[@version]`.
+    /// An address without version matches every version of the agent.
+    pub fn register_agent_middleware<M: Middleware + 'static>(&self, address: &str, middleware: M) {
+        self.add_middleware(MiddlewareScope::Agent(address.to_string()), Arc::new(middleware));
+    }
+
+    /// Wraps the invocations of a single operation, `[namespace.]name[@version].operation`.
+    pub fn register_operation_middleware<M: Middleware + 'static>(&self, name: &str, middleware: M) {
+        self.add_middleware(MiddlewareScope::Operation(name.to_string()), Arc::new(middleware));
+    }
+
+    fn add_middleware(&self, scope: MiddlewareScope, middleware: Arc<dyn Middleware>) {
+        self.update(|registry| registry.add_middleware(scope.clone(), middleware.clone()));
+    }
+
+    /// Middlewares of the ancestors, then this context's ones.
+    fn middleware_chain(&self) -> Chain {
+        let mut registries = Vec::new();
+        let mut context = Some(self);
+        while let Some(current) = context {
+            registries.push(current.inner.registry.load_full());
+            context = current.parent();
+        }
+        registries.reverse();
+        Chain::new(registries)
+    }
+
+    /// Runs `f` in an invocation scope, where each scoped service is built once and shared.
+    ///
+    /// Joins the active scope if any; `invoke` and `invoke_async` open one the same way.
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        Scope::enter(Scope::current_or_new(), f)
+    }
+
+    /// Reports every missing `Inject<T>` registration and every injection cycle among agents.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        validate(&self.registry(), self.parent())
+    }
+
+    /// Validates the dependency graph, then injects every agent.
+    pub fn try_init(&self) -> Result<(), ValidationError> {
+        let registry = self.inner.registry.load_full();
+        validate(&registry, self.parent())?;
+        for agent in registry.agents() {
             agent.inject_dependencies(self);
         }
+        Ok(())
+    }
+
+    /// Like [`try_init`](Self::try_init), but panics with the full report on an invalid dependency graph.
+    pub fn init(&self) {
+        if let Err(error) = self.try_init() {
+            panic!("{}", error);
+        }
+    }
+
+    /// Runs the `on_init` hook of every agent, then their `on_start` hook, dependencies first.
+    ///
+    /// Agents must be injected beforehand, by [`init`](Self::init) or [`AxorContextBuilder::build`].
+    /// The first failing hook aborts startup, and the agents already started are stopped.
+    pub fn start(&self) -> Result<(), LifecycleError> {
+        let agents = self.startup_order();
+        self.scope(|| {
+            for hook in [LifecycleHook::Init, LifecycleHook::Start] {
+                for (i, agent) in agents.iter().enumerate() {
+                    if let Err(error) = hook.call(agent.as_ref()) {
+                        if hook == LifecycleHook::Start {
+                            for started in agents[..i].iter().rev() {
+                                let _ = LifecycleHook::Stop.call(started.as_ref());
+                            }
+                        }
+                        return Err(lifecycle_error(agent, hook, error));
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Async counterpart of [`start`](Self::start), able to run both sync and `async` hooks.
+    pub async fn start_async(&self) -> Result<(), LifecycleError> {
+        let agents = self.startup_order();
+        let start = Box::pin(async move {
+            for hook in [LifecycleHook::Init, LifecycleHook::Start] {
+                for (i, agent) in agents.iter().enumerate() {
+                    if let Err(error) = hook.call_async(agent.as_ref()).await {
+                        if hook == LifecycleHook::Start {
+                            for started in agents[..i].iter().rev() {
+                                let _ = LifecycleHook::Stop.call_async(started.as_ref()).await;
+                            }
+                        }
+                        return Err(lifecycle_error(agent, hook, error));
+                    }
+                }
+            }
+            Ok(())
+        });
+        Scoped::new(Scope::current_or_new(), start).await
+    }
+
+    /// Runs the `on_stop` hook of every agent, dependents first.
+    ///
+    /// Every agent is stopped even when a hook fails, the first failure is returned.
+    pub fn stop(&self) -> Result<(), LifecycleError> {
+        let agents = self.startup_order();
+        self.scope(|| {
+            let mut result = Ok(());
+            for agent in agents.iter().rev() {
+                if let Err(error) = LifecycleHook::Stop.call(agent.as_ref()) {
+                    result = result.and(Err(lifecycle_error(agent, LifecycleHook::Stop, error)));
+                }
+            }
+            result
+        })
+    }
+
+    /// Async counterpart of [`stop`](Self::stop), able to run both sync and `async` hooks.
+    pub async fn stop_async(&self) -> Result<(), LifecycleError> {
+        let agents = self.startup_order();
+        let stop = Box::pin(async move {
+            let mut result = Ok(());
+            for agent in agents.iter().rev() {
+                if let Err(error) = LifecycleHook::Stop.call_async(agent.as_ref()).await {
+                    result = result.and(Err(lifecycle_error(agent, LifecycleHook::Stop, error)));
+                }
+            }
+            result
+        });
+        Scoped::new(Scope::current_or_new(), stop).await
     }
 
-    pub fn invoke(&self, payload: Payload) -> InvokeResult {
-        let (agent_name, _) = match payload.name.split_once('.') {
-            Some(res) => res,
-            None => {
-                return InvokeResult {
-                    operation: payload.name.to_string(),
-                    data: None,
-                    success: false,
+    /// Agents of this context, each one after the agents it injects.
+    fn startup_order(&self) -> Vec<Arc<dyn Agent>> {
+        let registry = self.registry();
+        dependency_order(&registry)
+            .into_iter()
+            .filter_map(|type_id| registry.agent(type_id).cloned())
+            .collect()
+    }
+
+    /// Routes `payload` here, or in the closest ancestor knowing its agent.
+    fn route(&self, payload: &Payload) -> Result<Route, InvokeError> {
+        let registry = self.registry();
+        let route = match registry.routes().route(payload) {
+            Err(error) if error.code == ErrorCode::AgentNotFound => {
+                match payload.agent_name() {
+                    Some(address) if registry.is_disabled_address(address) => {
+                        return Err(InvokeError::agent_disabled(address))
+                    }
+                    _ => Err(error),
                 }
             }
+            route => route,
         };
+        match (route, self.parent()) {
+            (Err(error), Some(parent)) if error.code == ErrorCode::AgentNotFound => {
+                parent.route(payload)
+            }
+            (route, _) => route,
+        }
+    }
 
-        let agents = self.agents.read().unwrap();
+    /// Runs the operation addressed by `payload`, wrapped in the matching middlewares.
+    pub fn invoke(&self, mut payload: Payload) -> InvokeResult {
+        let mut chain = self.middleware_chain();
+        self.scope(|| {
+            let short_circuit = chain.before(&mut payload, |payload| self.route(payload).ok().map(|route| route.agent));
+            let payload = Arc::new(payload);
+            let mut result = match short_circuit {
+                Some(result) => result,
+                None => match self.route(&payload) {
+                    Ok(Route { agent, index }) => {
+                        InvokeContext::new(payload.clone(), self.clone()).enter(|| match index {
+                            Some(index) => agent.call_operation_at(index, &payload),
+                            None => agent.call_operation(&payload),
+                        })
+                    }
+                    Err(error) => InvokeResult::err(payload.name.to_string(), error),
+                },
+            };
+            result.id = payload.id.clone();
+            chain.after(&payload, &mut result);
+            result
+        })
+    }
 
-        for agent in agents.values() {
-            if agent.name() == agent_name {
-                if payload.op_name().is_some() {
-                    let result = agent.call_operation(&payload);
-                    return result;
+    /// Async counterpart of [`invoke`](Self::invoke), able to run both sync and `async` operations.
+    pub async fn invoke_async(&self, mut payload: Payload) -> InvokeResult {
+        let mut chain = self.middleware_chain();
+        let scope = Scope::current_or_new();
+        let short_circuit = Scope::enter(scope.clone(), || chain.before(&mut payload, |payload| self.route(payload).ok().map(|route| route.agent)));
+        let payload = Arc::new(payload);
+        let mut result = match short_circuit {
+            Some(result) => result,
+            None => match self.route(&payload) {
+                Ok(Route { agent, index }) => {
+                    let call = match index {
+                        Some(index) => agent.call_operation_async_at(index, &payload),
+                        None => agent.call_operation_async(&payload),
+                    };
+                    let invocation = InvokeContext::new(payload.clone(), self.clone());
+                    Scoped::new(scope.clone(), Invoking::new(invocation, call)).await
                 }
+                Err(error) => InvokeResult::err(payload.name.to_string(), error),
+            },
+        };
+        result.id = payload.id.clone();
+        Scope::enter(scope, || chain.after(&payload, &mut result));
+        result
+    }
+
+    /// Runs several invocations, one after the other or concurrently, see [`BatchOptions`].
+    ///
+    /// Results are returned in the order of `payloads`, each echoing the correlation id of its payload.
+    pub fn invoke_batch(&self, payloads: Vec<Payload>, options: BatchOptions) -> Vec<InvokeResult> {
+        let failed = AtomicBool::new(false);
+        let run = |payload: Payload| {
+            if options.stop_on_failure && failed.load(Ordering::Relaxed) {
+                return batch::skipped(payload);
+            }
+            let result = self.invoke(payload);
+            if !result.success {
+                failed.store(true, Ordering::Relaxed);
+            }
+            result
+        };
+        match options.mode {
+            BatchMode::Sequential => payloads.into_iter().map(run).collect(),
+            BatchMode::Parallel => {
+                // Workers pull the next payload as they become free, so the thread count stays bounded
+                let workers = options.concurrency().min(payloads.len());
+                let mut results: Vec<Option<InvokeResult>> = vec![None; payloads.len()];
+                let pending = Mutex::new(payloads.into_iter().enumerate());
+                thread::scope(|scope| {
+                    let handles: Vec<_> = (0..workers)
+                        .map(|_| {
+                            scope.spawn(|| {
+                                let mut done = Vec::new();
+                                loop {
+                                    let next = pending.lock().unwrap().next();
+                                    let Some((i, payload)) = next else {
+                                        return done;
+                                    };
+                                    done.push((i, run(payload)));
+                                }
+                            })
+                        })
+                        .collect();
+                    for handle in handles {
+                        let done = handle.join().unwrap_or_else(|panic| panic::resume_unwind(panic));
+                        for (i, result) in done {
+                            results[i] = Some(result);
+                        }
+                    }
+                });
+                results.into_iter().flatten().collect()
             }
         }
-        InvokeResult {
-            operation: payload.name.to_string(),
-            data: None,
-            success: false,
+    }
+
+    /// Async counterpart of [`invoke_batch`](Self::invoke_batch), able to run both sync and `async` operations.
+    pub async fn invoke_batch_async(
+        &self,
+        payloads: Vec<Payload>,
+        options: BatchOptions,
+    ) -> Vec<InvokeResult> {
+        let failed = AtomicBool::new(false);
+        let run = |payload: Payload| {
+            let failed = &failed;
+            async move {
+                if options.stop_on_failure && failed.load(Ordering::Relaxed) {
+                    return batch::skipped(payload);
+                }
+                let result = self.invoke_async(payload).await;
+                if !result.success {
+                    failed.store(true, Ordering::Relaxed);
+                }
+                result
+            }
+        };
+        match options.mode {
+            BatchMode::Sequential => {
+                let mut results = Vec::new();
+                for payload in payloads {
+                    results.push(run(payload).await);
+                }
+                results
+            }
+            BatchMode::Parallel => {
+                stream::iter(payloads.into_iter().map(run))
+                    .buffered(options.concurrency())
+                    .collect()
+                    .await
+            }
         }
     }
 
+    /// Agents and operations served by this context, including the ones inherited from its parent.
     pub fn manifest(&self) -> AxorManifest {
-        let agents = self.agents.read().unwrap();
-        let mut list = Vec::new();
+        let registry = self.registry();
+        let mut list = self
+            .parent()
+            .map(|parent| parent.manifest().agents)
+            .unwrap_or_default();
 
-        for agent in agents.values() {
+        for agent in registry.agents() {
             let name = agent.name();
-            let ops = agent.operations().into_iter().map(|op| op.name.to_string()).collect();
-            list.push(AgentManifest {
+            let manifest = AgentManifest {
+                route: agent_route(agent.as_ref()),
                 name: name.to_string(),
-                operations: ops,
-            });
+                namespace: agent.namespace().map(str::to_string),
+                version: agent.version().map(str::to_string),
+                description: agent.description().map(str::to_string),
+                operations: agent
+                    .operations()
+                    .into_iter()
+                    .filter(|op| !op.hidden)
+                    .collect(),
+                dependencies: agent.dependencies(),
+                disabled: !registry.is_enabled(agent.as_any().type_id()),
+            };
+            // agents of a child shadow the parent's agents with the same route
+            match list.iter_mut().find(|inherited| inherited.route == manifest.route) {
+                Some(inherited) => *inherited = manifest,
+                None => list.push(manifest),
+            }
         }
 
         AxorManifest { agents: list }
@@ -120,8 +756,57 @@ pub struct AxorManifest {
 
 #[derive(Debug, Clone, Serialize)]
 pub struct AgentManifest {
+    /// Address used in payload names, `[namespace.]name[@version]`.
+    pub route: String,
     pub name: String,
-    pub operations: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub operations: Vec<OperationDescriptor>,
+    /// Injected services and agents
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<Dependency>,
+    /// Registered but not routed, see [`AxorContext::disable`]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RegisterError {
+    /// An agent of another type is already addressed by the same `[namespace.]name[@version]`.
+    DuplicateRoute { route: String },
+}
+
+impl fmt::Display for RegisterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegisterError::DuplicateRoute { route } => {
+                write!(f, "Another agent is already registered as `{}`", route)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegisterError {}
+
+fn lifecycle_error(agent: &Arc<dyn Agent>, hook: LifecycleHook, error: InvokeError) -> LifecycleError {
+    LifecycleError {
+        agent: agent_route(agent.as_ref()),
+        hook,
+        error,
+    }
+}
+
+fn not_exposable<I: ?Sized, S>() -> ! {
+    panic!(
+        "Cannot expose `{}` as `{}`: register it first",
+        type_name::<S>(),
+        type_name::<I>()
+    )
 }
 
 pub trait DowncastArc: Any + Send + Sync {
@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// Stable, machine-readable category of an invocation failure.
+///
+/// Runtimes (web, CLI, IPC) map these codes to their own status codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// The payload name is not of the form `Agent.operation`.
+    InvalidName,
+    /// No registered agent matches the payload name.
+    AgentNotFound,
+    /// The agent is registered but disabled with `AxorContext::disable`.
+    AgentDisabled,
+    /// The agent exists but does not expose the requested operation.
+    OperationNotFound,
+    /// The operation is `async` and was called through the sync `invoke` path.
+    AsyncOperation,
+    /// The payload data could not be deserialized into the operation input.
+    InvalidInput,
+    /// The operation ran and reported a business error.
+    OperationFailed,
+    /// The operation output could not be serialized, or another framework error occurred.
+    Internal,
+    /// Not run because an earlier item of its batch failed, see `BatchOptions::stop_on_failure`.
+    Skipped,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::InvalidName => "invalid_name",
+            ErrorCode::AgentNotFound => "agent_not_found",
+            ErrorCode::AgentDisabled => "agent_disabled",
+            ErrorCode::OperationNotFound => "operation_not_found",
+            ErrorCode::AsyncOperation => "async_operation",
+            ErrorCode::InvalidInput => "invalid_input",
+            ErrorCode::OperationFailed => "operation_failed",
+            ErrorCode::Internal => "internal",
+            ErrorCode::Skipped => "skipped",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error body carried by a failed [`InvokeResult`](crate::InvokeResult).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvokeError {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+impl InvokeError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn invalid_name(name: &str) -> Self {
+        Self::new(
+            ErrorCode::InvalidName,
+            format!("Invalid operation name `{}`, expected `Agent.operation`", name),
+        )
+    }
+
+    pub fn agent_not_found(agent: &str) -> Self {
+        Self::new(
+            ErrorCode::AgentNotFound,
+            format!("Agent `{}` not found", agent),
+        )
+    }
+
+    pub fn agent_disabled(agent: &str) -> Self {
+        Self::new(
+            ErrorCode::AgentDisabled,
+            format!("Agent `{}` is disabled", agent),
+        )
+    }
+
+    pub fn operation_not_found(name: &str) -> Self {
+        Self::new(
+            ErrorCode::OperationNotFound,
+            format!("Operation `{}` not found", name),
+        )
+    }
+
+    pub fn async_operation(name: &str) -> Self {
+        Self::new(
+            ErrorCode::AsyncOperation,
+            format!("Operation `{}` is async, use `invoke_async`", name),
+        )
+    }
+
+    /// An async lifecycle hook run through the sync `start`/`stop` path.
+    pub fn async_hook(hook: &str) -> Self {
+        Self::new(
+            ErrorCode::AsyncOperation,
+            format!("Hook `{}` is async, use `start_async` and `stop_async`", hook),
+        )
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidInput, message)
+    }
+
+    pub fn operation_failed(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::OperationFailed, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    pub fn skipped() -> Self {
+        Self::new(
+            ErrorCode::Skipped,
+            "Skipped after a failure in the same batch",
+        )
+    }
+}
+
+impl fmt::Display for InvokeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for InvokeError {}
+
+/// Conversion of an operation's `Err` value into an [`InvokeError`].
+///
+/// Implement it for your own error types to control the code, message and details
+/// returned when an operation declared as `-> Result<T, MyError>` fails.
+pub trait IntoInvokeError {
+    fn into_invoke_error(self) -> InvokeError;
+}
+
+impl IntoInvokeError for InvokeError {
+    fn into_invoke_error(self) -> InvokeError {
+        self
+    }
+}
+
+impl IntoInvokeError for anyhow::Error {
+    fn into_invoke_error(self) -> InvokeError {
+        match self.downcast::<InvokeError>() {
+            Ok(error) => error,
+            Err(error) => InvokeError::operation_failed(format!("{:#}", error)),
+        }
+    }
+}
+
+impl IntoInvokeError for Box<dyn std::error::Error + Send + Sync> {
+    fn into_invoke_error(self) -> InvokeError {
+        InvokeError::operation_failed(self.to_string())
+    }
+}
+
+impl IntoInvokeError for std::io::Error {
+    fn into_invoke_error(self) -> InvokeError {
+        InvokeError::operation_failed(self.to_string())
+    }
+}
+
+impl IntoInvokeError for std::fmt::Error {
+    fn into_invoke_error(self) -> InvokeError {
+        InvokeError::operation_failed(self.to_string())
+    }
+}
+
+impl IntoInvokeError for serde_json::Error {
+    fn into_invoke_error(self) -> InvokeError {
+        InvokeError::operation_failed(self.to_string())
+    }
+}
+
+impl IntoInvokeError for String {
+    fn into_invoke_error(self) -> InvokeError {
+        InvokeError::operation_failed(self)
+    }
+}
+
+impl IntoInvokeError for &str {
+    fn into_invoke_error(self) -> InvokeError {
+        InvokeError::operation_failed(self)
+    }
+}
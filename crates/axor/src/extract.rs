@@ -0,0 +1,193 @@
+use std::any::type_name;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::{Arc, OnceLock};
+
+use serde::de::DeserializeOwned;
+
+use crate::payload::Args;
+use crate::{InvokeContext, InvokeError, ParamsSchema, Payload};
+
+/// Source of an `#[operation]` parameter, resolved on each dynamic invocation.
+///
+/// Plain deserializable parameters are operation inputs, decoded from the payload data.
+/// Other implementations pull values from elsewhere, see [`Json`], [`Meta`], [`Raw`],
+/// [`Injected`] and `&InvokeContext`:
+///
+/// ```ignore
+/// #[operation]
+/// fn create(&self, tenant: Meta<Tenant>, repository: Injected<dyn Repository>, user: User) {
+///     repository.insert(&tenant, user);
+/// }
+/// ```
+pub trait FromInvocation<'a>: Sized {
+    /// Whether the parameter is one of the arguments decoded from the payload data.
+    const INPUT: bool = false;
+
+    /// Whether the parameter is listed in the manifest, inputs by default.
+    const LISTED: bool = Self::INPUT;
+
+    fn from_invocation(invocation: &'a Invocation<'a>, position: usize) -> Result<Self, InvokeError>;
+
+    /// Adds the parameter to the operation input schema, for agents declared with
+    /// `#[agent_impl(schema)]`. Plain inputs implementing `JsonSchema` are described from their type.
+    fn describe(schema: ParamsSchema, name: &str, required: bool) -> ParamsSchema {
+        let _ = (name, required);
+        schema
+    }
+}
+
+/// Operation input: the whole payload data when it is the only one, else the argument
+/// at its name or position.
+impl<'a, T: DeserializeOwned> FromInvocation<'a> for T {
+    const INPUT: bool = true;
+
+    fn from_invocation(invocation: &'a Invocation<'a>, position: usize) -> Result<Self, InvokeError> {
+        invocation.input(position)
+    }
+}
+
+/// Parameters of one dynamic call of an operation.
+pub struct Invocation<'a> {
+    payload: &'a Payload,
+    /// Names of every parameter of the operation
+    params: &'a [&'a str],
+    /// Whether each parameter is an input, see [`FromInvocation::INPUT`]
+    inputs: &'a [bool],
+    context: OnceLock<InvokeContext>,
+    /// Payload data split into arguments on first use, shared by every input
+    args: OnceLock<Result<Args<'a>, InvokeError>>,
+}
+
+impl<'a> Invocation<'a> {
+    /// Framework usage only
+    pub fn new(payload: &'a Payload, params: &'a [&'a str], inputs: &'a [bool]) -> Self {
+        Self {
+            payload,
+            params,
+            inputs,
+            context: OnceLock::new(),
+            args: OnceLock::new(),
+        }
+    }
+
+    pub fn payload(&self) -> &'a Payload {
+        self.payload
+    }
+
+    /// The current invocation, or an empty one when the operation is called outside of `invoke`.
+    pub fn context(&self) -> &InvokeContext {
+        self.context
+            .get_or_init(|| InvokeContext::current().unwrap_or_default())
+    }
+
+    /// Decodes the input parameter at `position` from the payload data.
+    pub fn input<T: DeserializeOwned>(&self, position: usize) -> Result<T, InvokeError> {
+        let count = self.inputs.iter().filter(|input| **input).count();
+        if count == 1 {
+            return self.payload.input_as();
+        }
+        let index = self.inputs[..position].iter().filter(|input| **input).count();
+        let name = self.params.get(position).copied().unwrap_or_default();
+        match self.args.get_or_init(|| self.payload.args()) {
+            Ok(args) => args.arg(index, name, count),
+            Err(error) => Err(error.clone()),
+        }
+    }
+}
+
+impl<'a> FromInvocation<'a> for &'a InvokeContext {
+    fn from_invocation(invocation: &'a Invocation<'a>, _position: usize) -> Result<Self, InvokeError> {
+        Ok(invocation.context())
+    }
+}
+
+/// The whole payload data, whatever the other parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Json<T>(pub T);
+
+impl<'a, T: DeserializeOwned> FromInvocation<'a> for Json<T> {
+    // Listed as the operation body, but not an argument: it takes the whole data
+    const LISTED: bool = true;
+
+    fn from_invocation(invocation: &'a Invocation<'a>, _position: usize) -> Result<Self, InvokeError> {
+        invocation.payload.input_as().map(Json)
+    }
+}
+
+/// The invoked payload, for full access to its name, data and metadata.
+#[derive(Debug, Clone, Copy)]
+pub struct Raw<'a>(pub &'a Payload);
+
+impl<'a> FromInvocation<'a> for Raw<'a> {
+    fn from_invocation(invocation: &'a Invocation<'a>, _position: usize) -> Result<Self, InvokeError> {
+        Ok(Raw(invocation.payload))
+    }
+}
+
+/// Metadata key read by a [`Meta`] parameter.
+///
+/// ```ignore
+/// struct Tenant;
+///
+/// impl MetaKey for Tenant {
+///     const KEY: &'static str = "tenant";
+/// }
+/// ```
+pub trait MetaKey {
+    const KEY: &'static str;
+}
+
+/// Payload metadata value at `K::KEY`, the invocation fails with `invalid_input` when missing.
+pub struct Meta<K: MetaKey>(pub String, pub PhantomData<K>);
+
+impl<K: MetaKey> Meta<K> {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into(), PhantomData)
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl<K: MetaKey> Deref for Meta<K> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<'a, K: MetaKey> FromInvocation<'a> for Meta<K> {
+    fn from_invocation(invocation: &'a Invocation<'a>, _position: usize) -> Result<Self, InvokeError> {
+        match invocation.payload.metadata(K::KEY) {
+            Some(value) => Ok(Meta::new(value)),
+            None => Err(InvokeError::invalid_input(format!("Missing metadata `{}`", K::KEY))),
+        }
+    }
+}
+
+/// Service resolved from the context serving the invocation.
+pub struct Injected<T: ?Sized>(pub Arc<T>);
+
+impl<T: ?Sized> Deref for Injected<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<'a, T: ?Sized + Send + Sync + 'static> FromInvocation<'a> for Injected<T> {
+    fn from_invocation(invocation: &'a Invocation<'a>, _position: usize) -> Result<Self, InvokeError> {
+        invocation
+            .context()
+            .context()
+            .and_then(|context| context.get_service::<T>())
+            .map(Injected)
+            .ok_or_else(|| {
+                InvokeError::internal(format!("Service not found: `{}`", type_name::<T>()))
+            })
+    }
+}
@@ -1,12 +1,67 @@
-use std::sync::{Arc, RwLock};
+use std::any::{type_name, TypeId};
+use std::sync::{Arc, OnceLock, RwLock};
 
+use serde::Serialize;
+
+use crate::context::WeakContext;
+use crate::service::{unerase, ServiceEntry, ServiceSlot};
 use crate::AxorContext;
 
-pub struct Inject<T> {
-    inner: RwLock<Option<Arc<T>>>,
+pub struct Inject<T: ?Sized> {
+    inner: RwLock<Option<Injection<T>>>,
+}
+
+enum Injection<T: ?Sized> {
+    /// Reads the registration on every access, so replaced services are picked up.
+    Slot(Arc<ServiceSlot>, WeakContext),
+    /// Looked up on first access, for fields marked `#[inject(lazy)]`.
+    Lazy(WeakContext, Option<String>, OnceLock<Box<Injection<T>>>),
 }
 
-impl<T> Default for Inject<T> {
+impl<T: ?Sized + Send + Sync + 'static> Injection<T> {
+    /// `None` when nothing is registered for `T` under `name`.
+    fn lookup(context: &AxorContext, name: Option<&str>) -> Option<Self> {
+        let (slot, owner) = context.service_slot(TypeId::of::<T>(), name)?;
+        Some(Injection::Slot(slot, owner.downgrade()))
+    }
+
+    fn lazy(context: &AxorContext, name: Option<&str>) -> Self {
+        Injection::Lazy(context.downgrade(), name.map(str::to_string), OnceLock::new())
+    }
+
+    fn get(&self) -> Option<Arc<T>> {
+        match self {
+            Injection::Slot(slot, context) => match &**slot.entry() {
+                ServiceEntry::Instance(service) => Some(unerase::<T>(service)),
+                ServiceEntry::Factory(factory) => {
+                    let context = context.upgrade().expect("Context dropped");
+                    Some(unerase::<T>(&factory.get(&context)))
+                }
+            },
+            Injection::Lazy(context, name, resolved) => {
+                if let Some(injected) = resolved.get() {
+                    return injected.get();
+                }
+                let context = context.upgrade().expect("Context dropped");
+                let injected = Self::lookup(&context, name.as_deref())?;
+                resolved.get_or_init(|| Box::new(injected)).get()
+            }
+        }
+    }
+}
+
+fn not_registered<T: ?Sized>(name: Option<&str>) -> ! {
+    match name {
+        Some(name) => panic!(
+            "Service not found: no `{}` registered as \"{}\"",
+            type_name::<T>(),
+            name
+        ),
+        None => panic!("Service not found: `{}` is not registered", type_name::<T>()),
+    }
+}
+
+impl<T: ?Sized> Default for Inject<T> {
     fn default() -> Self {
         Self {
             inner: RwLock::new(None),
@@ -14,14 +69,30 @@ impl<T> Default for Inject<T> {
     }
 }
 
-impl<T> Inject<T> {
-    pub fn resolve(&self) -> Arc<T> {
-        self.inner
-            .read()
-            .unwrap()
-            .as_ref()
-            .expect("Dependency not injected")
-            .clone()
+impl<T: ?Sized> Inject<T> {
+    /// Declares the dependency held by the agent field `field`.
+    pub fn dependency(field: &'static str) -> Dependency
+    where
+        T: 'static,
+    {
+        Dependency::new::<T>(field, DependencyKind::One)
+    }
+
+    pub fn resolve(&self) -> Arc<T>
+    where
+        T: Send + Sync + 'static,
+    {
+        let inner = self.inner.read().unwrap();
+        match inner.as_ref() {
+            Some(injected) => injected.get().unwrap_or_else(|| match injected {
+                Injection::Lazy(_, name, _) => not_registered::<T>(name.as_deref()),
+                _ => unreachable!("eager injections hold a service"),
+            }),
+            None => panic!(
+                "Dependency not injected: `{}`, build the context or call `AxorContext::init` first",
+                type_name::<T>()
+            ),
+        }
     }
 
     /// Framework usage only
@@ -29,7 +100,189 @@ impl<T> Inject<T> {
     where
         T: Send + Sync + 'static,
     {
-        let service = context.resolve::<T>();
-         *self.inner.write().unwrap() = Some(service);
+        self.inject(context, None);
+    }
+
+    /// Framework usage only, for fields marked `#[inject(name = "...")]`
+    pub fn from_context_named(&self, context: &AxorContext, name: &str)
+    where
+        T: Send + Sync + 'static,
+    {
+        self.inject(context, Some(name));
+    }
+
+    /// Framework usage only, for fields marked `#[inject(lazy)]`
+    pub fn from_context_lazy(&self, context: &AxorContext, name: Option<&str>)
+    where
+        T: Send + Sync + 'static,
+    {
+        *self.inner.write().unwrap() = Some(Injection::lazy(context, name));
+    }
+
+    fn inject(&self, context: &AxorContext, name: Option<&str>)
+    where
+        T: Send + Sync + 'static,
+    {
+        let injected =
+            Injection::lookup(context, name).unwrap_or_else(|| not_registered::<T>(name));
+        *self.inner.write().unwrap() = Some(injected);
+    }
+}
+
+/// Soft dependency: `resolve` returns `None` when `T` is not registered.
+pub struct InjectOptional<T: ?Sized> {
+    inner: RwLock<Option<Injection<T>>>,
+}
+
+impl<T: ?Sized> Default for InjectOptional<T> {
+    fn default() -> Self {
+        Self {
+            inner: RwLock::new(None),
+        }
+    }
+}
+
+impl<T: ?Sized> InjectOptional<T> {
+    /// Declares the dependency held by the agent field `field`.
+    pub fn dependency(field: &'static str) -> Dependency
+    where
+        T: 'static,
+    {
+        Dependency::new::<T>(field, DependencyKind::Optional)
+    }
+
+    pub fn resolve(&self) -> Option<Arc<T>>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.inner.read().unwrap().as_ref()?.get()
+    }
+
+    /// Framework usage only
+    pub fn from_context(&self, context: &AxorContext)
+    where
+        T: Send + Sync + 'static,
+    {
+        *self.inner.write().unwrap() = Injection::lookup(context, None);
+    }
+
+    /// Framework usage only, for fields marked `#[inject(name = "...")]`
+    pub fn from_context_named(&self, context: &AxorContext, name: &str)
+    where
+        T: Send + Sync + 'static,
+    {
+        *self.inner.write().unwrap() = Injection::lookup(context, Some(name));
+    }
+
+    /// Framework usage only, for fields marked `#[inject(lazy)]`
+    pub fn from_context_lazy(&self, context: &AxorContext, name: Option<&str>)
+    where
+        T: Send + Sync + 'static,
+    {
+        *self.inner.write().unwrap() = Some(Injection::lazy(context, name));
+    }
+}
+
+/// Every service registered with [`AxorContext::register_into`] for `T`, in registration order.
+///
+/// ```ignore
+/// #[agent]
+/// struct FormAgent {
+///     validators: InjectAll<dyn Validator>,
+/// }
+/// ```
+pub struct InjectAll<T: ?Sized> {
+    inner: RwLock<Vec<Arc<T>>>,
+}
+
+impl<T: ?Sized> Default for InjectAll<T> {
+    fn default() -> Self {
+        Self {
+            inner: RwLock::new(Vec::new()),
+        }
+    }
+}
+
+impl<T: ?Sized> InjectAll<T> {
+    /// Declares the collection held by the agent field `field`.
+    pub fn dependency(field: &'static str) -> Dependency
+    where
+        T: 'static,
+    {
+        Dependency::new::<T>(field, DependencyKind::All)
+    }
+
+    pub fn resolve(&self) -> Vec<Arc<T>> {
+        self.inner.read().unwrap().clone()
+    }
+
+    /// Framework usage only
+    pub fn from_context(&self, context: &AxorContext)
+    where
+        T: Send + Sync + 'static,
+    {
+        *self.inner.write().unwrap() = context.get_all::<T>();
+    }
+}
+
+/// Service or agent an agent depends on, declared by an `Inject<T>` field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Dependency {
+    pub field: &'static str,
+    /// Qualifier set with `#[inject(name = "...")]`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<&'static str>,
+    #[serde(skip_serializing_if = "DependencyKind::is_one")]
+    pub kind: DependencyKind,
+    /// Set with `#[inject(lazy)]`: looked up on first access instead of by `init`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub lazy: bool,
+    #[serde(skip)]
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+}
+
+impl Dependency {
+    fn new<T: ?Sized + 'static>(field: &'static str, kind: DependencyKind) -> Self {
+        Self {
+            field,
+            name: None,
+            kind,
+            lazy: false,
+            type_id: TypeId::of::<T>(),
+            type_name: type_name::<T>(),
+        }
+    }
+
+    pub fn named(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    pub fn lazy(mut self) -> Self {
+        self.lazy = true;
+        self
+    }
+
+    /// Whether validation fails when nothing is registered for this dependency.
+    pub fn is_required(&self) -> bool {
+        self.kind == DependencyKind::One && !self.lazy
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyKind {
+    /// `Inject<T>`, a single registered service
+    One,
+    /// `InjectOptional<T>`, a single service if registered
+    Optional,
+    /// `InjectAll<T>`, every service registered into `T`, possibly none
+    All,
+}
+
+impl DependencyKind {
+    fn is_one(&self) -> bool {
+        matches!(self, DependencyKind::One)
     }
 }
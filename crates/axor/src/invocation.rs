@@ -0,0 +1,180 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::{AxorContext, Payload};
+
+/// The invocation an operation is serving: its payload, caller and context.
+///
+/// Operations receive it by declaring a `&InvokeContext` parameter, which is not part of their
+/// input. Direct calls pass `&InvokeContext::default()`:
+///
+/// ```ignore
+/// #[operation]
+/// fn greet(&self, invocation: &InvokeContext, name: String) -> String {
+///     match invocation.metadata("locale") {
+///         Some("fr") => format!("Bonjour {}", name),
+///         _ => format!("Hello {}", name),
+///     }
+/// }
+///
+/// agent.greet(&InvokeContext::default(), "Ada".into());
+/// ```
+#[derive(Clone, Default)]
+pub struct InvokeContext {
+    payload: Option<Arc<Payload>>,
+    context: Option<AxorContext>,
+}
+
+thread_local! {
+    static CURRENT_INVOCATION: RefCell<Option<InvokeContext>> = const { RefCell::new(None) };
+}
+
+impl InvokeContext {
+    pub(crate) fn new(payload: Arc<Payload>, context: AxorContext) -> Self {
+        Self {
+            payload: Some(payload),
+            context: Some(context),
+        }
+    }
+
+    /// The invocation running on this thread, `None` outside of `invoke` and `invoke_async`.
+    pub fn current() -> Option<Self> {
+        CURRENT_INVOCATION.with(|current| current.borrow().clone())
+    }
+
+    /// Runs `f` with this invocation as the current one.
+    pub(crate) fn enter<R>(self, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<InvokeContext>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let previous = self.0.take();
+                CURRENT_INVOCATION.with(|current| *current.borrow_mut() = previous);
+            }
+        }
+
+        let previous = CURRENT_INVOCATION.with(|current| current.borrow_mut().replace(self));
+        let _restore = Restore(previous);
+        f()
+    }
+
+    /// The invoked payload, `None` for direct calls.
+    pub fn payload(&self) -> Option<&Payload> {
+        self.payload.as_deref()
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.payload()?.id.as_deref()
+    }
+
+    /// Full operation name, `[namespace.]name[@version].operation`
+    pub fn name(&self) -> Option<&str> {
+        Some(&self.payload()?.name)
+    }
+
+    pub fn metadata(&self, key: &str) -> Option<&str> {
+        self.payload()?.metadata(key)
+    }
+
+    /// Caller authenticated by a middleware, see [`Payload::principal`].
+    pub fn principal(&self) -> Option<&Principal> {
+        self.payload()?.principal.as_ref()
+    }
+
+    /// Whether the caller gave up on the invocation, see [`Payload::cancellation`].
+    pub fn is_cancelled(&self) -> bool {
+        self.payload()
+            .is_some_and(|payload| payload.cancellation.is_cancelled())
+    }
+
+    /// The context serving the invocation, `None` for direct calls.
+    pub fn context(&self) -> Option<&AxorContext> {
+        self.context.as_ref()
+    }
+}
+
+impl fmt::Debug for InvokeContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InvokeContext")
+            .field("payload", &self.payload)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Authenticated caller of an invocation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Principal {
+    pub id: String,
+    pub roles: Vec<String>,
+}
+
+impl Principal {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            roles: Vec::new(),
+        }
+    }
+
+    pub fn with_role(mut self, role: impl Into<String>) -> Self {
+        self.roles.push(role.into());
+        self
+    }
+
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+}
+
+/// Cancellation flag shared by a payload and the runtime that sent it.
+///
+/// Operations are not interrupted: long-running ones check
+/// [`InvokeContext::is_cancelled`] and stop early. The default flag is never cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct Cancellation(Option<Arc<AtomicBool>>);
+
+impl Cancellation {
+    pub fn new() -> Self {
+        Self(Some(Arc::default()))
+    }
+
+    pub fn cancel(&self) {
+        if let Some(cancelled) = &self.0 {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0
+            .as_ref()
+            .is_some_and(|cancelled| cancelled.load(Ordering::Relaxed))
+    }
+}
+
+/// Future polled with its invocation current, so it stays readable across `.await` points.
+pub(crate) struct Invoking<F> {
+    invocation: InvokeContext,
+    inner: F,
+}
+
+impl<F> Invoking<F> {
+    pub fn new(invocation: InvokeContext, inner: F) -> Self {
+        Self { invocation, inner }
+    }
+}
+
+impl<F: Future + Unpin> Future for Invoking<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.invocation
+            .clone()
+            .enter(|| Pin::new(&mut this.inner).poll(cx))
+    }
+}
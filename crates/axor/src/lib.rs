@@ -11,16 +11,40 @@
 
 
 mod agent;
+mod batch;
+mod builder;
 mod context;
+mod error;
+mod extract;
 mod operation;
 mod inject;
+mod invocation;
+mod lifecycle;
+mod middleware;
 mod payload;
+mod registry;
+mod routing;
+mod service;
+mod validation;
+mod schema;
 
 pub use agent::*;
+pub use batch::{BatchMode, BatchOptions};
+pub use builder::*;
 pub use context::*;
+pub use error::*;
+pub use extract::*;
 pub use operation::*;
 pub use inject::*;
+pub use invocation::{Cancellation, InvokeContext, Principal};
+pub use lifecycle::{LifecycleError, LifecycleHook};
+pub use middleware::Middleware;
 pub use payload::*;
+pub use service::{Interface, ServiceLifetime};
+pub use validation::{MissingDependency, ValidationError};
+pub use schema::*;
+
+pub use async_trait::async_trait;
 
 /// Auto-imports all the commonly used types and macros for agent development.
 #[doc(hidden)]
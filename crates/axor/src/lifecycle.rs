@@ -0,0 +1,69 @@
+use std::fmt;
+
+use crate::{Agent, InvokeError};
+
+/// Agent hooks run by [`AxorContext::start`](crate::AxorContext::start) and
+/// [`AxorContext::stop`](crate::AxorContext::stop).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LifecycleHook {
+    Init,
+    Start,
+    Stop,
+}
+
+impl LifecycleHook {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LifecycleHook::Init => "on_init",
+            LifecycleHook::Start => "on_start",
+            LifecycleHook::Stop => "on_stop",
+        }
+    }
+
+    pub(crate) fn call(&self, agent: &dyn Agent) -> Result<(), InvokeError> {
+        match self {
+            LifecycleHook::Init => agent.on_init(),
+            LifecycleHook::Start => agent.on_start(),
+            LifecycleHook::Stop => agent.on_stop(),
+        }
+    }
+
+    pub(crate) async fn call_async(&self, agent: &dyn Agent) -> Result<(), InvokeError> {
+        match self {
+            LifecycleHook::Init => agent.on_init_async().await,
+            LifecycleHook::Start => agent.on_start_async().await,
+            LifecycleHook::Stop => agent.on_stop_async().await,
+        }
+    }
+}
+
+impl fmt::Display for LifecycleHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// First hook that failed while starting or stopping a context.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LifecycleError {
+    /// Route of the failing agent
+    pub agent: String,
+    pub hook: LifecycleHook,
+    pub error: InvokeError,
+}
+
+impl fmt::Display for LifecycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Agent `{}` failed in `{}`: {}",
+            self.agent, self.hook, self.error.message
+        )
+    }
+}
+
+impl std::error::Error for LifecycleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+use crate::registry::Registry;
+use crate::routing::qualified_name;
+use crate::{agent_route, Agent, InvokeResult, Payload};
+
+/// Interceptor wrapped around every matching invocation.
+///
+/// `before` hooks run in registration order, then the operation, then the `after` hooks
+/// in reverse order:
+///
+/// ```ignore
+/// struct Auth;
+///
+/// impl Middleware for Auth {
+///     fn before(&self, payload: &mut Payload) -> ControlFlow<InvokeResult> {
+///         if !payload.has_data() {
+///             return ControlFlow::Break(InvokeResult::err(
+///                 payload.name.clone(),
+///                 InvokeError::operation_failed("Unauthorized"),
+///             ));
+///         }
+///         ControlFlow::Continue(())
+///     }
+/// }
+///
+/// context.register_agent_middleware("users", Auth);
+/// ```
+pub trait Middleware: Send + Sync {
+    /// Called before routing, may rewrite the payload.
+    /// `Break` skips the operation and the remaining middlewares, and returns its result.
+    fn before(&self, payload: &mut Payload) -> ControlFlow<InvokeResult> {
+        let _ = payload;
+        ControlFlow::Continue(())
+    }
+
+    /// Called with the result, may rewrite it.
+    /// Only middlewares whose `before` let the invocation through are called.
+    fn after(&self, payload: &Payload, result: &mut InvokeResult) {
+        let _ = (payload, result);
+    }
+}
+
+/// Invocations a middleware applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum MiddlewareScope {
+    Global,
+    /// `[namespace.]name[@version]`, the unversioned address matching every version
+    Agent(String),
+    /// `[namespace.]name[@version].operation`
+    Operation(String),
+}
+
+/// Middlewares of a registry, indexed by scope so that an invocation only visits
+/// the ones matching it, whatever the number registered for other agents and operations.
+#[derive(Clone, Default)]
+pub(crate) struct Middlewares {
+    /// Registration order
+    list: Vec<Arc<dyn Middleware>>,
+    /// Positions in `list` of the global middlewares
+    global: Vec<usize>,
+    /// Address -> positions in `list`
+    agents: HashMap<String, Vec<usize>>,
+    /// Address -> operation -> positions in `list`
+    operations: HashMap<String, HashMap<String, Vec<usize>>>,
+}
+
+impl Middlewares {
+    pub fn add(&mut self, scope: MiddlewareScope, middleware: Arc<dyn Middleware>) {
+        let position = self.list.len();
+        self.list.push(middleware);
+        match scope {
+            MiddlewareScope::Global => self.global.push(position),
+            MiddlewareScope::Agent(address) => {
+                self.agents.entry(address).or_default().push(position)
+            }
+            MiddlewareScope::Operation(name) => {
+                // Names without an operation match no invocation
+                if let Some((address, op_name)) = name.rsplit_once('.') {
+                    self.operations
+                        .entry(address.to_string())
+                        .or_default()
+                        .entry(op_name.to_string())
+                        .or_default()
+                        .push(position);
+                }
+            }
+        }
+    }
+
+    pub fn get(&self, position: usize) -> &Arc<dyn Middleware> {
+        &self.list[position]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Position of the first middleware from `from` matching `target`.
+    pub fn next(&self, target: &Target, from: usize) -> Option<usize> {
+        let agent_scopes = |address: &String| {
+            let agents = self.agents.get(address);
+            let operations = self.operations.get(address).and_then(|ops| ops.get(&target.op_name));
+            [agents, operations]
+        };
+        std::iter::once(&self.global)
+            .chain(target.addresses.iter().flat_map(agent_scopes).flatten())
+            .filter_map(|positions| positions.get(positions.partition_point(|p| *p < from)))
+            .min()
+            .copied()
+    }
+}
+
+/// What an invocation is matched against: the addresses of the agent it is routed to,
+/// so that an unversioned alias meets the middlewares of the version it reaches.
+///
+/// Payloads reaching no agent are matched on the address they name.
+pub(crate) struct Target {
+    /// Payload name the target was resolved from
+    name: String,
+    /// Versioned address first, then the unversioned one
+    addresses: Vec<String>,
+    op_name: String,
+}
+
+impl Target {
+    pub fn new(payload: &Payload, agent: Option<&dyn Agent>) -> Self {
+        let addresses = match agent {
+            Some(agent) => {
+                let route = agent_route(agent);
+                let name = qualified_name(agent);
+                if route == name {
+                    vec![route]
+                } else {
+                    vec![route, name]
+                }
+            }
+            None => {
+                let address = payload.agent_name();
+                let unversioned = address
+                    .and_then(|address| address.split_once('@'))
+                    .map(|(name, _)| name);
+                address
+                    .into_iter()
+                    .chain(unversioned)
+                    .map(str::to_string)
+                    .collect()
+            }
+        };
+        Self {
+            name: payload.name.clone(),
+            addresses,
+            op_name: payload.op_name().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+/// Middlewares of one invocation, remembering which ones must see the result.
+///
+/// Holds the registries of the context and its ancestors, ancestors first.
+pub(crate) struct Chain {
+    registries: Vec<Arc<Registry>>,
+    /// `(registry, position)` of each middleware whose `before` let the invocation through
+    entered: Vec<(usize, usize)>,
+}
+
+impl Chain {
+    pub fn new(registries: Vec<Arc<Registry>>) -> Self {
+        Self {
+            registries,
+            entered: Vec::new(),
+        }
+    }
+
+    /// Runs the matching `before` hooks, `Some` when one of them short-circuits the invocation.
+    ///
+    /// `resolve` gives the agent a payload is routed to. It is called again whenever a
+    /// middleware rewrites the name, so that the rewritten name selects the middlewares run after it.
+    pub fn before(
+        &mut self,
+        payload: &mut Payload,
+        resolve: impl Fn(&Payload) -> Option<Arc<dyn Agent>>,
+    ) -> Option<InvokeResult> {
+        if self.registries.iter().all(|registry| registry.middlewares().is_empty()) {
+            return None;
+        }
+        let target = |payload: &Payload| Target::new(payload, resolve(payload).as_deref());
+        let mut current = target(payload);
+        for (level, registry) in self.registries.iter().enumerate() {
+            let middlewares = registry.middlewares();
+            let mut from = 0;
+            while let Some(position) = middlewares.next(&current, from) {
+                if let ControlFlow::Break(result) = middlewares.get(position).before(payload) {
+                    return Some(result);
+                }
+                self.entered.push((level, position));
+                from = position + 1;
+                if payload.name != current.name {
+                    current = target(payload);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn after(&self, payload: &Payload, result: &mut InvokeResult) {
+        for (level, position) in self.entered.iter().rev() {
+            self.registries[*level]
+                .middlewares()
+                .get(*position)
+                .after(payload, result);
+        }
+    }
+}
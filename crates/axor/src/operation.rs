@@ -1,4 +1,59 @@
-#[derive(Clone)]
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct OperationDescriptor {
     pub name: &'static str,
+    /// Declared parameters, in call order (`self` excluded).
+    pub params: Vec<ParamDescriptor>,
+    /// Rust type of the returned value (the `Ok` type for `Result` outputs), if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_type: Option<&'static str>,
+    /// JSON Schema of `Payload::data` (`#[agent_impl(schema)]` agents only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_schema: Option<Value>,
+    /// JSON Schema of `InvokeResult::data` (`#[agent_impl(schema)]` agents only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<Value>,
+    /// `description` argument of `#[operation]`, or the operation rustdoc.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<&'static str>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<&'static str>,
+    /// Hidden operations can be called directly but are neither routed nor listed in the manifest.
+    #[serde(skip)]
+    pub hidden: bool,
+}
+
+impl OperationDescriptor {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_param(mut self, name: &'static str, type_name: &'static str) -> Self {
+        self.params.push(ParamDescriptor { name, type_name });
+        self
+    }
+
+    pub fn with_description(mut self, description: &'static str) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn with_output(mut self, type_name: &'static str) -> Self {
+        self.output_type = Some(type_name);
+        self
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ParamDescriptor {
+    pub name: &'static str,
+    /// Rust type as written in the operation signature.
+    pub type_name: &'static str,
 }
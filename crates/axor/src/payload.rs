@@ -1,18 +1,42 @@
-use serde::{Serialize, Deserialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::RawValue;
 use serde_json::Value;
 
+use crate::{Cancellation, InvokeError, Principal};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Payload {
+    /// Correlation id, echoed on the [`InvokeResult`](crate::InvokeResult)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     pub name: String,
-    pub data: Option<Value>,
+    /// Input data, see [`data`](Self::data)
+    #[serde(rename = "data", default)]
+    body: Body,
+    /// Caller identity, locale, deadline, trace headers... read by middlewares and operations
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, String>,
+    /// Caller authenticated by a middleware, never read from the wire
+    #[serde(skip)]
+    pub principal: Option<Principal>,
+    /// Cancelled by the runtime when the caller goes away
+    #[serde(skip)]
+    pub cancellation: Cancellation,
     pub success: bool 
 }
 
 impl Payload {
     pub fn new(name: impl Into<String>) -> Self {
         Self {
+            id: None,
             name: name.into(),
-            data: None,
+            body: Body::Empty,
+            metadata: HashMap::new(),
+            principal: None,
+            cancellation: Cancellation::default(),
             success: true
         }
     }
@@ -20,20 +44,174 @@ impl Payload {
     pub fn with_data<T: Serialize>(name: impl Into<String>, data: &T) -> Self {
         let value = serde_json::to_value(data).expect("Invalid input serialization");
         Self {
+            id: None,
             name: name.into(),
-            data: Some(value),
+            body: Body::Value(value),
+            metadata: HashMap::new(),
+            principal: None,
+            cancellation: Cancellation::default(),
             success: true
         }
     }
 
-    pub fn input_as<T: for<'de> Deserialize<'de>>(&self) -> Option<T> {
-        self.data
-            .as_ref()
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
+    /// Parses a JSON payload, keeping its data as raw JSON.
+    ///
+    /// Operations then decode their inputs directly from `json`, without building and
+    /// walking an intermediate `Value` tree: prefer it in runtimes receiving large documents.
+    pub fn from_slice(json: &[u8]) -> Result<Self, serde_json::Error> {
+        #[derive(Deserialize)]
+        struct RawPayload {
+            #[serde(default)]
+            id: Option<String>,
+            name: String,
+            data: Option<Box<RawValue>>,
+            #[serde(default)]
+            metadata: HashMap<String, String>,
+            success: bool,
+        }
+
+        let raw: RawPayload = serde_json::from_slice(json)?;
+        Ok(Self {
+            id: raw.id,
+            name: raw.name,
+            body: raw.data.map_or(Body::Empty, Body::Raw),
+            metadata: raw.metadata,
+            principal: None,
+            cancellation: Cancellation::default(),
+            success: raw.success,
+        })
+    }
+
+    /// Input data, parsed on demand for payloads read by [`from_slice`](Self::from_slice).
+    pub fn data(&self) -> Option<Cow<'_, Value>> {
+        match &self.body {
+            Body::Empty => None,
+            Body::Value(value) => Some(Cow::Borrowed(value)),
+            Body::Raw(raw) => Some(Cow::Owned(
+                serde_json::from_str(raw.get()).expect("Raw data is valid JSON"),
+            )),
+        }
+    }
+
+    /// Input data of a payload read by [`from_slice`](Self::from_slice), until replaced by [`set_data`](Self::set_data).
+    pub fn raw_data(&self) -> Option<&RawValue> {
+        match &self.body {
+            Body::Raw(raw) => Some(raw),
+            _ => None,
+        }
+    }
+
+    pub fn has_data(&self) -> bool {
+        !matches!(self.body, Body::Empty)
+    }
+
+    /// Replaces the input data, e.g. from a middleware rewriting the payload.
+    pub fn set_data(&mut self, data: Option<Value>) {
+        self.body = data.map_or(Body::Empty, Body::Value);
+    }
+
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets the caller, usually from an authentication middleware.
+    pub fn with_principal(mut self, principal: Principal) -> Self {
+        self.principal = Some(principal);
+        self
+    }
+
+    /// Shares `cancellation` with the runtime, which cancels it when the caller goes away.
+    pub fn with_cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    pub fn metadata(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).map(String::as_str)
+    }
+
+    /// Decodes the input data, borrowing it rather than cloning it.
+    pub fn input_as<T: for<'de> Deserialize<'de>>(&self) -> Result<T, InvokeError> {
+        let decoded = match &self.body {
+            Body::Raw(raw) => serde_json::from_str(raw.get()),
+            Body::Value(value) => T::deserialize(value),
+            // Inputs such as `Option<T>` or `()` accept a missing body
+            Body::Empty => {
+                return T::deserialize(&Value::Null)
+                    .map_err(|_| InvokeError::invalid_input("Missing input data"))
+            }
+        };
+        decoded.map_err(|e| InvokeError::invalid_input(format!("Invalid input: {}", e)))
+    }
+
+    /// Decodes the argument at `index` of an operation declared with `params`.
+    ///
+    /// Data may be a JSON object keyed by parameter names or a positional JSON array.
+    /// A missing argument is decoded from `null`, so `Option<T>` parameters can be omitted.
+    pub fn arg_as<T: for<'de> Deserialize<'de>>(
+        &self,
+        index: usize,
+        params: &[&str],
+    ) -> Result<T, InvokeError> {
+        let name = params.get(index).copied().unwrap_or_default();
+        self.arg_at(index, name, params.len())
+    }
+
+    /// Decodes the argument `name`, at `index` among `count` arguments, see [`arg_as`](Self::arg_as).
+    pub(crate) fn arg_at<T: for<'de> Deserialize<'de>>(
+        &self,
+        index: usize,
+        name: &str,
+        count: usize,
+    ) -> Result<T, InvokeError> {
+        self.args()?.arg(index, name, count)
+    }
+
+    /// Splits the input data into the arguments of a multi-input operation.
+    ///
+    /// Raw data is scanned once, each argument keeping its slice of the original JSON.
+    pub(crate) fn args(&self) -> Result<Args<'_>, InvokeError> {
+        let invalid = || {
+            InvokeError::invalid_input(
+                "Expected an object of named arguments or an array of positional arguments",
+            )
+        };
+        match &self.body {
+            Body::Empty | Body::Value(Value::Null) => Ok(Args::Empty),
+            Body::Value(Value::Object(map)) => Ok(Args::Named(map)),
+            Body::Value(Value::Array(values)) => Ok(Args::Positional(values)),
+            Body::Value(_) => Err(invalid()),
+            Body::Raw(raw) => {
+                let json = raw.get();
+                let parse_error =
+                    |e: serde_json::Error| InvokeError::invalid_input(format!("Invalid input: {}", e));
+                match json.trim_start().as_bytes().first() {
+                    Some(b'{') => serde_json::from_str(json).map(Args::RawNamed).map_err(parse_error),
+                    Some(b'[') => serde_json::from_str(json)
+                        .map(Args::RawPositional)
+                        .map_err(parse_error),
+                    _ => Err(invalid()),
+                }
+            }
+        }
+    }
+
+    pub fn agent_name(&self) -> Option<&str> {
+        if let Some( (agent_name, _)) = self.name.rsplit_once('.') {
+            Some(agent_name)
+        }else {
+            None
+        }
     }
 
     pub fn op_name(&self) -> Option<&str> {
-        if let Some( (_, op_name)) = self.name.split_once('.') {
+        if let Some( (_, op_name)) = self.name.rsplit_once('.') {
             Some(op_name)
         }else {
             None
@@ -43,4 +221,76 @@ impl Payload {
     pub fn op_name_unchecked(&self) -> &str {
         self.op_name().expect("Operation name not defined")
     }
-}
\ No newline at end of file
+}
+
+/// Arguments of a multi-input operation, borrowed from the payload data.
+pub(crate) enum Args<'a> {
+    Empty,
+    Named(&'a serde_json::Map<String, Value>),
+    Positional(&'a [Value]),
+    RawNamed(HashMap<String, &'a RawValue>),
+    RawPositional(Vec<&'a RawValue>),
+}
+
+impl Args<'_> {
+    /// Decodes the argument `name`, at `index` among `count` arguments.
+    /// A missing argument is decoded from `null`.
+    pub fn arg<T: for<'de> Deserialize<'de>>(
+        &self,
+        index: usize,
+        name: &str,
+        count: usize,
+    ) -> Result<T, InvokeError> {
+        let len = match self {
+            Args::Positional(values) => values.len(),
+            Args::RawPositional(values) => values.len(),
+            _ => 0,
+        };
+        if len > count {
+            return Err(InvokeError::invalid_input(format!(
+                "Expected at most {} arguments, got {}",
+                count, len
+            )));
+        }
+        let decoded = match self {
+            Args::Empty => None,
+            Args::Named(map) => map.get(name).map(T::deserialize),
+            Args::Positional(values) => values.get(index).map(T::deserialize),
+            Args::RawNamed(map) => map.get(name).map(|raw| serde_json::from_str(raw.get())),
+            Args::RawPositional(values) => values.get(index).map(|raw| serde_json::from_str(raw.get())),
+        };
+        match decoded {
+            Some(decoded) => decoded
+                .map_err(|e| InvokeError::invalid_input(format!("Invalid argument `{}`: {}", name, e))),
+            None => serde_json::from_value(Value::Null)
+                .map_err(|_| InvokeError::invalid_input(format!("Missing argument `{}`", name))),
+        }
+    }
+}
+
+/// Input data of a payload, parsed or kept as raw JSON.
+#[derive(Debug, Clone, Default)]
+enum Body {
+    #[default]
+    Empty,
+    Value(Value),
+    /// Left unparsed by [`Payload::from_slice`], decoded straight into operation inputs
+    Raw(Box<RawValue>),
+}
+
+impl Serialize for Body {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Body::Empty => serializer.serialize_none(),
+            Body::Value(value) => value.serialize(serializer),
+            // Raw data is written back as is
+            Body::Raw(raw) => raw.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Body {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<Value>::deserialize(deserializer).map(|data| data.map_or(Body::Empty, Body::Value))
+    }
+}
@@ -1,2 +1,6 @@
-pub use crate::{Agent, AxorContext, Inject, Payload, InvokeResult, OperationDescriptor};
-pub use axor_macros::{agent, agent_impl, operation};
+pub use crate::{Agent, AxorContext, Dependency, Inject, InjectAll, InjectOptional, Payload, InvokeResult, InvokeError, InvokeContext, FromInvocation, Invocation, Json, Meta, MetaKey, Raw, Injected, IntoInvokeError, ErrorCode, OperationDescriptor, ParamDescriptor, ServiceLifetime, Interface, Middleware};
+pub use crate::async_trait;
+pub use crate::{Describe, DescribeExtractor, ParamsSchema};
+#[cfg(feature = "schema")]
+pub use crate::{schema_for, DescribeBody, DescribeInput};
+pub use axor_macros::{agent, agent_impl, interface, on_init, on_service_change, on_start, on_stop, operation};
@@ -0,0 +1,209 @@
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::middleware::{Middleware, MiddlewareScope, Middlewares};
+use crate::routing::{is_addressed_by, RouteIndex};
+use crate::service::{
+    erase, AnyService, Interface, ServiceEntry, ServiceFactory, ServiceLifetime,
+    ServiceSlot,
+};
+use crate::{agent_route, Agent};
+
+/// Immutable snapshot of everything registered in a context.
+///
+/// Readers load the current snapshot without locking, writers publish a modified copy.
+#[derive(Clone, Default)]
+pub(crate) struct Registry {
+    agents: HashMap<TypeId, Arc<dyn Agent>>,
+    /// Agents in registration order
+    order: Vec<TypeId>,
+    /// Registered agents left out of routing
+    disabled: HashSet<TypeId>,
+    services: HashMap<TypeId, Arc<ServiceSlot>>,
+    /// Qualified services, by type then name
+    named_services: HashMap<TypeId, HashMap<String, Arc<ServiceSlot>>>,
+    /// Interfaces each concrete service is exposed as, see [`add_interface`](Self::add_interface)
+    exposed: HashMap<TypeId, Vec<TypeId>>,
+    /// Multi-bindings, each item an erased `Arc<T>`, in registration order
+    collections: HashMap<TypeId, Vec<AnyService>>,
+    middlewares: Middlewares,
+    routes: Arc<RouteIndex>,
+}
+
+impl Registry {
+    /// Adds an agent, also resolvable as a service. Call [`rebuild_routes`](Self::rebuild_routes) afterwards.
+    pub fn add_agent<T: Agent + 'static>(&mut self, agent: Arc<T>) {
+        let type_id = TypeId::of::<T>();
+        if self.agents.insert(type_id, agent.clone()).is_some() {
+            self.order.retain(|id| *id != type_id);
+        }
+        self.order.push(type_id);
+        self.add_entry(type_id, None, ServiceEntry::Instance(erase(agent)));
+    }
+
+    /// Removes an agent and its service registration. Call [`rebuild_routes`](Self::rebuild_routes) afterwards.
+    pub fn remove_agent(&mut self, type_id: TypeId) -> Option<Arc<dyn Agent>> {
+        let agent = self.agents.remove(&type_id)?;
+        self.order.retain(|id| *id != type_id);
+        self.disabled.remove(&type_id);
+        self.services.remove(&type_id);
+        Some(agent)
+    }
+
+    /// Enables or disables the agents addressed by `address`, returns `false` if there is none.
+    /// Call [`rebuild_routes`](Self::rebuild_routes) afterwards.
+    pub fn set_enabled(&mut self, address: &str, enabled: bool) -> bool {
+        let addressed: Vec<_> = self
+            .order
+            .iter()
+            .filter(|type_id| is_addressed_by(self.agents[*type_id].as_ref(), address))
+            .copied()
+            .collect();
+        for type_id in &addressed {
+            if enabled {
+                self.disabled.remove(type_id);
+            } else {
+                self.disabled.insert(*type_id);
+            }
+        }
+        !addressed.is_empty()
+    }
+
+    pub fn is_enabled(&self, type_id: TypeId) -> bool {
+        !self.disabled.contains(&type_id)
+    }
+
+    /// Whether `address` designates a disabled agent.
+    pub fn is_disabled_address(&self, address: &str) -> bool {
+        self.disabled
+            .iter()
+            .any(|type_id| is_addressed_by(self.agents[type_id].as_ref(), address))
+    }
+
+    /// Type id of a registered agent other than `except` routed as `route`.
+    pub fn agent_routed_as(&self, route: &str, except: TypeId) -> Option<TypeId> {
+        self.order
+            .iter()
+            .find(|type_id| {
+                **type_id != except && agent_route(self.agents[*type_id].as_ref()) == route
+            })
+            .copied()
+    }
+
+    pub fn add_service<T: ?Sized + Send + Sync + 'static>(&mut self, service: Arc<T>) {
+        self.add_entry(TypeId::of::<T>(), None, ServiceEntry::Instance(erase(service)));
+    }
+
+    pub fn add_factory(&mut self, type_id: TypeId, factory: Arc<ServiceFactory>) {
+        self.add_entry(type_id, None, ServiceEntry::Factory(factory));
+    }
+
+    /// Registers `entry` in a new slot: fields injected from a previous registration keep it.
+    pub fn add_entry(&mut self, type_id: TypeId, name: Option<&str>, entry: ServiceEntry) {
+        let slot = ServiceSlot::new(entry);
+        match name {
+            None => {
+                self.services.insert(type_id, slot);
+            }
+            Some(name) => {
+                self.named_services
+                    .entry(type_id)
+                    .or_default()
+                    .insert(name.to_string(), slot);
+            }
+        }
+    }
+
+    /// Exposes the service registered as `S` under the interface `I` as well, sharing its instances.
+    ///
+    /// `I` reads through the registration of `S`, so replacing `S` reaches it too.
+    /// Returns `false` when `S` is not registered.
+    pub fn add_interface<I, S>(&mut self) -> bool
+    where
+        I: ?Sized + Interface<S>,
+        S: Send + Sync + 'static,
+    {
+        if !self.services.contains_key(&TypeId::of::<S>()) {
+            return false;
+        }
+        // The registration of `S` keeps applying its own lifetime
+        let factory = ServiceFactory::new(ServiceLifetime::Transient, |context| {
+            I::upcast(context.resolve::<S>())
+        });
+        self.add_entry(TypeId::of::<I>(), None, ServiceEntry::Factory(Arc::new(factory)));
+        let exposed = self.exposed.entry(TypeId::of::<S>()).or_default();
+        if !exposed.contains(&TypeId::of::<I>()) {
+            exposed.push(TypeId::of::<I>());
+        }
+        true
+    }
+
+    /// Interfaces the service registered as `type_id` is exposed as.
+    pub fn exposed_as(&self, type_id: TypeId) -> &[TypeId] {
+        self.exposed.get(&type_id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn add_into(&mut self, type_id: TypeId, service: AnyService) {
+        self.collections.entry(type_id).or_default().push(service);
+    }
+
+    pub fn add_middleware(&mut self, scope: MiddlewareScope, middleware: Arc<dyn Middleware>) {
+        self.middlewares.add(scope, middleware);
+    }
+
+    pub fn rebuild_routes(&mut self) {
+        let enabled = self
+            .order
+            .iter()
+            .filter(|type_id| self.is_enabled(**type_id))
+            .filter_map(|type_id| self.agents.get(type_id));
+        self.routes = Arc::new(RouteIndex::build(enabled));
+    }
+
+    pub fn agent(&self, type_id: TypeId) -> Option<&Arc<dyn Agent>> {
+        self.agents.get(&type_id)
+    }
+
+    /// Agents in registration order
+    pub fn agents(&self) -> impl Iterator<Item = &Arc<dyn Agent>> {
+        self.order.iter().filter_map(|type_id| self.agents.get(type_id))
+    }
+
+    /// Type ids of agents, in registration order
+    pub fn agent_ids(&self) -> &[TypeId] {
+        &self.order
+    }
+
+    /// The unnamed service of type `type_id`, or the one registered as `name`.
+    pub fn service(&self, type_id: TypeId, name: Option<&str>) -> Option<&Arc<ServiceSlot>> {
+        match name {
+            None => self.services.get(&type_id),
+            Some(name) => self.named_services.get(&type_id)?.get(name),
+        }
+    }
+
+    pub fn collection(&self, type_id: TypeId) -> &[AnyService] {
+        self.collections.get(&type_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Names registered for services of type `type_id`, sorted.
+    pub fn service_names(&self, type_id: TypeId) -> Vec<&str> {
+        let mut names: Vec<_> = self
+            .named_services
+            .get(&type_id)
+            .into_iter()
+            .flat_map(|services| services.keys().map(String::as_str))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    pub fn middlewares(&self) -> &Middlewares {
+        &self.middlewares
+    }
+
+    pub fn routes(&self) -> &RouteIndex {
+        &self.routes
+    }
+}
@@ -0,0 +1,134 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{agent_route, Agent, InvokeError, Payload};
+
+#[derive(Clone)]
+pub(crate) struct Route {
+    pub agent: Arc<dyn Agent>,
+    /// Position of the operation in `Agent::operations`, `None` for undeclared operations.
+    pub index: Option<usize>,
+}
+
+/// Routing tables, rebuilt on registration so that invocation cost
+/// does not depend on the number of agents and operations.
+#[derive(Default)]
+pub(crate) struct RouteIndex {
+    /// `address.operation` -> handler
+    operations: HashMap<String, Route>,
+    /// `[namespace.]name[@version]` -> agent, plus `[namespace.]name` for the preferred version
+    agents: HashMap<String, Arc<dyn Agent>>,
+    /// Addresses claimed by more than one agent, the last registered one wins
+    duplicates: Vec<String>,
+}
+
+impl RouteIndex {
+    pub fn build<'a>(agents: impl Iterator<Item = &'a Arc<dyn Agent>>) -> Self {
+        let mut index = RouteIndex::default();
+        // Without a version, the unversioned agent is preferred, then the highest version
+        let mut preferred: HashMap<String, &Arc<dyn Agent>> = HashMap::new();
+
+        for agent in agents {
+            let address = agent_route(agent.as_ref());
+            if index.agents.contains_key(&address) {
+                index.duplicates.push(address.clone());
+            }
+            index.add(address, agent);
+            if agent.version().is_some() {
+                let entry = preferred.entry(qualified_name(agent.as_ref())).or_insert(agent);
+                if prefers(agent.version(), entry.version()) {
+                    *entry = agent;
+                }
+            }
+        }
+        for (qualified_name, agent) in preferred {
+            if !index.agents.contains_key(&qualified_name) {
+                index.add(qualified_name, agent);
+            }
+        }
+        index
+    }
+
+    fn add(&mut self, address: String, agent: &Arc<dyn Agent>) {
+        for (i, op) in agent.operations().iter().enumerate() {
+            if !op.hidden {
+                self.operations.insert(
+                    format!("{}.{}", address, op.name),
+                    Route {
+                        agent: agent.clone(),
+                        index: Some(i),
+                    },
+                );
+            }
+        }
+        self.agents.insert(address, agent.clone());
+    }
+
+    pub fn duplicates(&self) -> &[String] {
+        &self.duplicates
+    }
+
+    /// Finds the handler addressed by `[namespace.]name[@version].operation`.
+    pub fn route(&self, payload: &Payload) -> Result<Route, InvokeError> {
+        if let Some(route) = self.operations.get(payload.name.as_str()) {
+            return Ok(route.clone());
+        }
+
+        // Slow path: undeclared operations are left to the agent, which reports them
+        match payload.name.rsplit_once('.') {
+            Some((agent_path, op_name)) if !agent_path.is_empty() && !op_name.is_empty() => {
+                match self.agents.get(agent_path) {
+                    Some(agent) => Ok(Route {
+                        agent: agent.clone(),
+                        index: None,
+                    }),
+                    None => Err(InvokeError::agent_not_found(agent_path)),
+                }
+            }
+            _ => Err(InvokeError::invalid_name(&payload.name)),
+        }
+    }
+}
+
+/// `[namespace.]name`, the address of an agent without its version.
+pub(crate) fn qualified_name(agent: &dyn Agent) -> String {
+    match agent.namespace() {
+        Some(namespace) => format!("{}.{}", namespace, agent.name()),
+        None => agent.name().to_string(),
+    }
+}
+
+/// Whether `address` designates `agent`, with or without its version.
+pub(crate) fn is_addressed_by(agent: &dyn Agent, address: &str) -> bool {
+    agent_route(agent) == address || qualified_name(agent) == address
+}
+
+/// Whether version `a` should be routed rather than `b` for an unversioned address.
+fn prefers(a: Option<&str>, b: Option<&str>) -> bool {
+    match (a, b) {
+        (None, _) => true,
+        (Some(_), None) => false,
+        (Some(a), Some(b)) => compare_versions(a, b) == Ordering::Greater,
+    }
+}
+
+/// Compares dot-separated versions, numerically when both segments are numbers.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let mut a_parts = a.split('.');
+    let mut b_parts = b.split('.');
+    loop {
+        let ordering = match (a_parts.next(), b_parts.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                _ => a.cmp(b),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
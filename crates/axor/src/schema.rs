@@ -0,0 +1,160 @@
+//! JSON Schemas of operation inputs and outputs, for agents declared with `#[agent_impl(schema)]`.
+//!
+//! Generating them from Rust types requires the `schema` feature.
+
+use std::marker::PhantomData;
+
+#[cfg(feature = "schema")]
+use schemars::{JsonSchema, SchemaGenerator};
+#[cfg(feature = "schema")]
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::FromInvocation;
+#[cfg(feature = "schema")]
+use crate::Json;
+
+/// Root JSON Schema of `T`.
+#[cfg(feature = "schema")]
+pub fn schema_for<T: ?Sized + JsonSchema>() -> Value {
+    SchemaGenerator::default()
+        .into_root_schema_for::<T>()
+        .to_value()
+}
+
+/// Object schema describing the named arguments of a multi-parameter operation.
+pub struct ParamsSchema {
+    #[cfg(feature = "schema")]
+    generator: SchemaGenerator,
+    properties: Map<String, Value>,
+    required: Vec<Value>,
+    /// Root schema of the first parameter, the input schema of single-input operations
+    first: Option<Value>,
+}
+
+impl Default for ParamsSchema {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParamsSchema {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(feature = "schema")]
+            generator: SchemaGenerator::default(),
+            properties: Map::new(),
+            required: Vec::new(),
+            first: None,
+        }
+    }
+
+    #[cfg(feature = "schema")]
+    pub fn param<T: ?Sized + JsonSchema>(mut self, name: &str, required: bool) -> Self {
+        if self.properties.is_empty() {
+            self.first = Some(schema_for::<T>());
+        }
+        let schema = self.generator.subschema_for::<T>().to_value();
+        self.insert(name, schema, required);
+        self
+    }
+
+    /// Adds a parameter described by a hand-written `schema`, for custom extractors.
+    pub fn param_schema(mut self, name: &str, schema: Value, required: bool) -> Self {
+        if self.properties.is_empty() {
+            self.first = Some(schema.clone());
+        }
+        self.insert(name, schema, required);
+        self
+    }
+
+    fn insert(&mut self, name: &str, schema: Value, required: bool) {
+        self.properties.insert(name.to_string(), schema);
+        if required {
+            self.required.push(Value::String(name.to_string()));
+        }
+    }
+
+    /// Input schema of an operation: none without inputs, the schema of its only input,
+    /// or an object of named arguments.
+    pub fn input_schema(self) -> Option<Value> {
+        match self.properties.len() {
+            0 => None,
+            1 => self.first,
+            _ => Some(self.build()),
+        }
+    }
+
+    #[cfg_attr(not(feature = "schema"), allow(unused_mut))]
+    pub fn build(mut self) -> Value {
+        let mut schema = Map::new();
+        #[cfg(feature = "schema")]
+        if let Some(meta_schema) = self.generator.settings().meta_schema.as_deref() {
+            schema.insert("$schema".into(), meta_schema.into());
+        }
+        schema.insert("type".into(), "object".into());
+        schema.insert("properties".into(), Value::Object(self.properties));
+        schema.insert("required".into(), Value::Array(self.required));
+        #[cfg(feature = "schema")]
+        {
+            let definitions = self.generator.take_definitions(true);
+            if !definitions.is_empty() {
+                schema.insert("$defs".into(), Value::Object(definitions));
+            }
+        }
+        Value::Object(schema)
+    }
+}
+
+/// Parameter of type `T` being described, framework usage only.
+///
+/// Plain inputs implementing `JsonSchema` are described from their type, [`Json`](crate::Json)
+/// bodies from their content type, other parameters through [`FromInvocation::describe`]:
+/// the generated code calls `(&&&Describe::<T>::new()).describe(..)`, and method resolution
+/// picks the first impl that applies.
+#[doc(hidden)]
+pub struct Describe<'a, T>(PhantomData<(&'a (), T)>);
+
+impl<T> Describe<'_, T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+#[cfg(feature = "schema")]
+pub trait DescribeInput {
+    fn describe(&self, schema: ParamsSchema, name: &str, required: bool) -> ParamsSchema;
+}
+
+#[cfg(feature = "schema")]
+impl<T: DeserializeOwned + JsonSchema> DescribeInput for &&Describe<'_, T> {
+    fn describe(&self, schema: ParamsSchema, name: &str, required: bool) -> ParamsSchema {
+        schema.param::<T>(name, required)
+    }
+}
+
+#[doc(hidden)]
+#[cfg(feature = "schema")]
+pub trait DescribeBody {
+    fn describe(&self, schema: ParamsSchema, name: &str, required: bool) -> ParamsSchema;
+}
+
+#[cfg(feature = "schema")]
+impl<T: JsonSchema> DescribeBody for &Describe<'_, Json<T>> {
+    fn describe(&self, schema: ParamsSchema, name: &str, required: bool) -> ParamsSchema {
+        schema.param::<T>(name, required)
+    }
+}
+
+#[doc(hidden)]
+pub trait DescribeExtractor {
+    fn describe(&self, schema: ParamsSchema, name: &str, required: bool) -> ParamsSchema;
+}
+
+impl<'a, T: FromInvocation<'a>> DescribeExtractor for Describe<'a, T> {
+    fn describe(&self, schema: ParamsSchema, name: &str, required: bool) -> ParamsSchema {
+        T::describe(schema, name, required)
+    }
+}
@@ -0,0 +1,206 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll};
+
+use arc_swap::{ArcSwap, Guard};
+
+use crate::AxorContext;
+
+/// Type-erased `Arc<T>`, so unsized services such as `dyn Trait` are stored like any other.
+pub(crate) type AnyService = Arc<dyn Any + Send + Sync>;
+
+pub(crate) fn erase<T: ?Sized + Send + Sync + 'static>(service: Arc<T>) -> AnyService {
+    Arc::new(service)
+}
+
+pub(crate) fn unerase<T: ?Sized + 'static>(service: &AnyService) -> Arc<T> {
+    service
+        .downcast_ref::<Arc<T>>()
+        .expect("Type mismatch when downcasting service")
+        .clone()
+}
+
+/// Trait object that services implementing its trait can be registered as.
+///
+/// Implemented by `#[interface]` on the trait definition:
+///
+/// ```ignore
+/// #[interface]
+/// trait Logger: Send + Sync {
+///     fn log(&self, message: &str);
+/// }
+///
+/// context.register_as::<dyn Logger, _>(ConsoleLogger);
+/// ```
+pub trait Interface<S>: Send + Sync + 'static {
+    fn upcast(service: Arc<S>) -> Arc<Self>;
+}
+
+/// How often a factory registered with [`AxorContext::register_factory`] is called.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceLifetime {
+    /// Built on first resolve, then shared.
+    Singleton,
+    /// Built on every resolve.
+    Transient,
+    /// Built once per invocation scope, see [`AxorContext::scope`].
+    Scoped,
+}
+
+/// Registered service, either a prebuilt instance or a factory.
+#[derive(Clone)]
+pub(crate) enum ServiceEntry {
+    Instance(AnyService),
+    Factory(Arc<ServiceFactory>),
+}
+
+impl ServiceEntry {
+    pub fn get(&self, context: &AxorContext) -> AnyService {
+        match self {
+            ServiceEntry::Instance(service) => service.clone(),
+            ServiceEntry::Factory(factory) => factory.get(context),
+        }
+    }
+
+    pub fn resolve<T: ?Sized + 'static>(&self, context: &AxorContext) -> Arc<T> {
+        unerase(&self.get(context))
+    }
+}
+
+/// Swappable registration, shared by registry snapshots and by the injected fields reading it.
+pub(crate) struct ServiceSlot(ArcSwap<ServiceEntry>);
+
+impl ServiceSlot {
+    pub fn new(entry: ServiceEntry) -> Arc<Self> {
+        Arc::new(Self(ArcSwap::from_pointee(entry)))
+    }
+
+    pub fn entry(&self) -> Guard<Arc<ServiceEntry>> {
+        self.0.load()
+    }
+
+    pub fn replace(&self, entry: ServiceEntry) {
+        self.0.store(Arc::new(entry));
+    }
+}
+
+pub(crate) struct ServiceFactory {
+    id: u64,
+    lifetime: ServiceLifetime,
+    create: Box<dyn Fn(&AxorContext) -> AnyService + Send + Sync>,
+    singleton: OnceLock<AnyService>,
+}
+
+impl ServiceFactory {
+    pub fn new<T, F>(lifetime: ServiceLifetime, factory: F) -> Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+        F: Fn(&AxorContext) -> Arc<T> + Send + Sync + 'static,
+    {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            lifetime,
+            create: Box::new(move |context| erase(factory(context))),
+            singleton: OnceLock::new(),
+        }
+    }
+
+    pub fn get(&self, context: &AxorContext) -> AnyService {
+        match self.lifetime {
+            ServiceLifetime::Singleton => self
+                .singleton
+                .get_or_init(|| (self.create)(context))
+                .clone(),
+            ServiceLifetime::Transient => (self.create)(context),
+            ServiceLifetime::Scoped => {
+                let scope = Scope::current().unwrap_or_else(|| {
+                    panic!("Scoped service resolved outside of an invocation scope")
+                });
+                if let Some(service) = scope.get(self.id) {
+                    return service;
+                }
+                // Built without holding the scope lock: the factory may resolve other scoped services.
+                let service = (self.create)(context);
+                scope.insert(self.id, service)
+            }
+        }
+    }
+}
+
+/// Instances of scoped services, shared by everything running in the same invocation.
+#[derive(Default)]
+pub(crate) struct Scope {
+    instances: Mutex<HashMap<u64, AnyService>>,
+}
+
+thread_local! {
+    static CURRENT_SCOPE: RefCell<Option<Arc<Scope>>> = const { RefCell::new(None) };
+}
+
+impl Scope {
+    pub fn current() -> Option<Arc<Scope>> {
+        CURRENT_SCOPE.with(|current| current.borrow().clone())
+    }
+
+    /// The active scope, or a new one when called outside of any scope.
+    pub fn current_or_new() -> Arc<Scope> {
+        Self::current().unwrap_or_default()
+    }
+
+    /// Runs `f` with `scope` as the active scope.
+    pub fn enter<R>(scope: Arc<Scope>, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<Arc<Scope>>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let previous = self.0.take();
+                CURRENT_SCOPE.with(|current| *current.borrow_mut() = previous);
+            }
+        }
+
+        let previous = CURRENT_SCOPE.with(|current| current.borrow_mut().replace(scope));
+        let _restore = Restore(previous);
+        f()
+    }
+
+    fn get(&self, id: u64) -> Option<AnyService> {
+        self.instances.lock().unwrap().get(&id).cloned()
+    }
+
+    /// Keeps the first instance stored for `id`.
+    fn insert(&self, id: u64, service: AnyService) -> AnyService {
+        self.instances
+            .lock()
+            .unwrap()
+            .entry(id)
+            .or_insert(service)
+            .clone()
+    }
+}
+
+/// Future polled with its scope active, so scoped services survive `.await` points.
+pub(crate) struct Scoped<F> {
+    scope: Arc<Scope>,
+    inner: F,
+}
+
+impl<F> Scoped<F> {
+    pub fn new(scope: Arc<Scope>, inner: F) -> Self {
+        Self { scope, inner }
+    }
+}
+
+impl<F: Future + Unpin> Future for Scoped<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        Scope::enter(this.scope.clone(), || Pin::new(&mut this.inner).poll(cx))
+    }
+}
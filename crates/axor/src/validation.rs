@@ -0,0 +1,183 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{agent_route, AxorContext, DependencyKind};
+use crate::registry::Registry;
+
+/// Dependency graph problems found before serving.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Every required `Inject<T>` field whose type is not registered.
+    pub missing: Vec<MissingDependency>,
+    /// Agent routes forming injection cycles, the first one repeated at the end.
+    pub cycles: Vec<Vec<String>>,
+}
+
+impl ValidationError {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.cycles.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingDependency {
+    pub agent: String,
+    pub field: &'static str,
+    /// Qualifier of the wanted service, if any
+    pub name: Option<&'static str>,
+    pub type_name: &'static str,
+}
+
+impl fmt::Display for MissingDependency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{} requires `{}`", self.agent, self.field, self.type_name)?;
+        if let Some(name) = self.name {
+            write!(f, " named \"{}\"", name)?;
+        }
+        write!(f, ", which is not registered")
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid dependency graph:")?;
+        for dependency in &self.missing {
+            write!(f, "\n  - {}", dependency)?;
+        }
+        for cycle in &self.cycles {
+            write!(f, "\n  - dependency cycle: {}", cycle.join(" -> "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Checks the `Inject<T>` fields declared by every agent of the registry.
+///
+/// Services registered by `parent` and its ancestors count as registered.
+pub(crate) fn validate(
+    registry: &Registry,
+    parent: Option<&AxorContext>,
+) -> Result<(), ValidationError> {
+    let mut error = ValidationError::default();
+
+    for agent in registry.agents() {
+        for dependency in agent.dependencies() {
+            let registered = registry.service(dependency.type_id, dependency.name).is_some()
+                || parent.is_some_and(|parent| {
+                    parent
+                        .service_slot(dependency.type_id, dependency.name)
+                        .is_some()
+                });
+            if dependency.is_required() && !registered {
+                error.missing.push(MissingDependency {
+                    agent: agent_route(agent.as_ref()),
+                    field: dependency.field,
+                    name: dependency.name,
+                    type_name: dependency.type_name,
+                });
+            }
+        }
+    }
+    error.cycles = find_cycles(registry);
+
+    if error.is_empty() {
+        Ok(())
+    } else {
+        Err(error)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    InProgress,
+    Done,
+}
+
+/// Agent-to-agent injections, by injecting agent.
+fn agent_edges(registry: &Registry) -> HashMap<TypeId, Vec<TypeId>> {
+    registry
+        .agent_ids()
+        .iter()
+        .filter_map(|type_id| Some((type_id, registry.agent(*type_id)?)))
+        .map(|(type_id, agent)| {
+            let targets = agent
+                .dependencies()
+                .into_iter()
+                // lazy fields break cycles, the agent is only looked up on first access
+                .filter(|dependency| dependency.kind != DependencyKind::All && !dependency.lazy)
+                .filter(|dependency| dependency.name.is_none())
+                .map(|dependency| dependency.type_id)
+                .filter(|type_id| registry.agent(*type_id).is_some())
+                .collect();
+            (*type_id, targets)
+        })
+        .collect()
+}
+
+/// Depth-first search over agent-to-agent injections, in registration order.
+fn find_cycles(registry: &Registry) -> Vec<Vec<String>> {
+    let edges = agent_edges(registry);
+    let mut visits = HashMap::new();
+    let mut cycles = Vec::new();
+    let mut order = Vec::new();
+    for type_id in registry.agent_ids() {
+        let mut path = Vec::new();
+        visit(*type_id, &edges, &mut visits, &mut path, &mut cycles, &mut order);
+    }
+
+    cycles
+        .into_iter()
+        .map(|cycle: Vec<TypeId>| {
+            cycle
+                .iter()
+                .filter_map(|type_id| registry.agent(*type_id))
+                .map(|agent| agent_route(agent.as_ref()))
+                .collect()
+        })
+        .collect()
+}
+
+/// Agents of the registry, each one after the agents it injects, otherwise in registration order.
+pub(crate) fn dependency_order(registry: &Registry) -> Vec<TypeId> {
+    let edges = agent_edges(registry);
+    let mut visits = HashMap::new();
+    let mut order = Vec::new();
+    for type_id in registry.agent_ids() {
+        visit(*type_id, &edges, &mut visits, &mut Vec::new(), &mut Vec::new(), &mut order);
+    }
+    order
+}
+
+fn visit(
+    node: TypeId,
+    edges: &HashMap<TypeId, Vec<TypeId>>,
+    visits: &mut HashMap<TypeId, Visit>,
+    path: &mut Vec<TypeId>,
+    cycles: &mut Vec<Vec<TypeId>>,
+    order: &mut Vec<TypeId>,
+) {
+    match visits.get(&node) {
+        Some(Visit::Done) => return,
+        Some(Visit::InProgress) => {
+            if let Some(start) = path.iter().position(|id| *id == node) {
+                let mut cycle = path[start..].to_vec();
+                cycle.push(node);
+                cycles.push(cycle);
+            }
+            return;
+        }
+        None => {}
+    }
+
+    visits.insert(node, Visit::InProgress);
+    path.push(node);
+    for target in edges.get(&node).into_iter().flatten() {
+        visit(*target, edges, visits, path, cycles, order);
+    }
+    path.pop();
+    visits.insert(node, Visit::Done);
+    order.push(node);
+}
@@ -0,0 +1,118 @@
+use axor::prelude::*;
+use serde_json::json;
+
+#[agent(name = "users", version = "1")]
+struct UserAgentV1;
+
+#[agent_impl]
+impl UserAgentV1 {
+    #[operation]
+    fn get(&self) -> &'static str {
+        "v1"
+    }
+}
+
+#[agent(name = "users", version = "2")]
+struct UserAgentV2;
+
+#[agent_impl]
+impl UserAgentV2 {
+    #[operation]
+    fn get(&self) -> &'static str {
+        "v2"
+    }
+}
+
+#[agent(name = "invoices", namespace = "billing")]
+struct InvoiceAgent;
+
+#[agent_impl]
+impl InvoiceAgent {
+    #[operation]
+    fn count(&self) -> u32 {
+        12
+    }
+}
+
+fn context() -> AxorContext {
+    let context = AxorContext::new();
+    context.register(UserAgentV1);
+    context.register(UserAgentV2);
+    context.register(InvoiceAgent);
+    context
+}
+
+#[test]
+fn versions_coexist() {
+    let context = context();
+
+    let response = context.invoke(Payload::new("users@1.get"));
+    assert_eq!(response.data, Some(json!("v1")));
+
+    let response = context.invoke(Payload::new("users@2.get"));
+    assert_eq!(response.data, Some(json!("v2")));
+
+    // Without a version, the highest one is used
+    let response = context.invoke(Payload::new("users.get"));
+    assert_eq!(response.data, Some(json!("v2")));
+
+    let response = context.invoke(Payload::new("users@3.get"));
+    assert_eq!(response.error.unwrap().code, ErrorCode::AgentNotFound);
+}
+
+#[test]
+fn struct_name_is_no_longer_routed() {
+    let response = context().invoke(Payload::new("UserAgentV1.get"));
+    assert_eq!(response.error.unwrap().code, ErrorCode::AgentNotFound);
+}
+
+#[test]
+fn namespaced_agent() {
+    let context = context();
+
+    let response = context.invoke(Payload::new("billing.invoices.count"));
+    assert_eq!(response.data, Some(json!(12)));
+
+    let response = context.invoke(Payload::new("invoices.count"));
+    assert_eq!(response.error.unwrap().code, ErrorCode::AgentNotFound);
+}
+
+#[test]
+fn manifest_reports_routes() {
+    let manifest = context().manifest();
+    let mut routes: Vec<_> = manifest
+        .agents
+        .iter()
+        .map(|agent| agent.route.as_str())
+        .collect();
+    routes.sort();
+    assert_eq!(routes, vec!["billing.invoices", "users@1", "users@2"]);
+
+    let invoices = manifest
+        .agents
+        .iter()
+        .find(|agent| agent.name == "invoices")
+        .unwrap();
+    assert_eq!(invoices.namespace.as_deref(), Some("billing"));
+    assert!(invoices.version.is_none());
+}
+
+#[test]
+fn routes_follow_registrations() {
+    let context = AxorContext::new();
+    context.register(UserAgentV1);
+    assert_eq!(
+        context.invoke(Payload::new("users.get")).data,
+        Some(json!("v1"))
+    );
+
+    context.register(UserAgentV2);
+    assert_eq!(
+        context.invoke(Payload::new("users.get")).data,
+        Some(json!("v2"))
+    );
+    assert_eq!(
+        context.invoke(Payload::new("users@1.get")).data,
+        Some(json!("v1"))
+    );
+}
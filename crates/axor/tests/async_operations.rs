@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use axor::prelude::*;
+use futures::executor::block_on;
+use serde_json::json;
+
+trait Repository: Send + Sync {
+    fn find(&self, id: u32) -> Option<String>;
+}
+
+struct MemoryRepository;
+
+impl Repository for MemoryRepository {
+    fn find(&self, id: u32) -> Option<String> {
+        (id == 1).then(|| "Alice".to_string())
+    }
+}
+
+#[agent]
+struct UserAgent {
+    repository: Inject<Arc<dyn Repository>>,
+}
+
+#[agent_impl]
+impl UserAgent {
+    #[operation]
+    async fn find(&self, id: u32) -> Option<String> {
+        // Simulates an I/O round trip
+        futures::future::ready(()).await;
+        self.repository.resolve().find(id)
+    }
+
+    #[operation]
+    async fn exists(&self, id: u32) -> anyhow::Result<bool> {
+        match self.find(id).await {
+            Some(_) => Ok(true),
+            None => anyhow::bail!("User {} not found", id),
+        }
+    }
+
+    #[operation]
+    fn count(&self) -> u32 {
+        1
+    }
+}
+
+fn context() -> AxorContext {
+    let context = AxorContext::new();
+    context.register(UserAgent::default());
+    context.register_service::<Arc<dyn Repository>>(Arc::new(MemoryRepository));
+    context.init();
+    context
+}
+
+#[test]
+fn direct_async_call() {
+    let context = context();
+    let agent = context.resolve::<UserAgent>();
+    assert_eq!(block_on(agent.find(1)), Some("Alice".to_string()));
+}
+
+#[test]
+fn invoke_async_operation() {
+    let context = context();
+
+    let response = block_on(context.invoke_async(Payload::with_data("UserAgent.find", &1)));
+    assert!(response.success);
+    assert_eq!(response.data, Some(json!("Alice")));
+
+    let response = block_on(context.invoke_async(Payload::with_data("UserAgent.exists", &2)));
+    assert!(!response.success);
+    assert_eq!(response.error.unwrap().code, ErrorCode::OperationFailed);
+}
+
+#[test]
+fn invoke_async_runs_sync_operations() {
+    let response = block_on(context().invoke_async(Payload::new("UserAgent.count")));
+    assert!(response.success);
+    assert_eq!(response.data, Some(json!(1)));
+}
+
+#[test]
+fn sync_invoke_rejects_async_operation() {
+    let response = context().invoke(Payload::with_data("UserAgent.find", &1));
+    assert!(!response.success);
+    assert_eq!(response.error.unwrap().code, ErrorCode::AsyncOperation);
+
+    let response = context().invoke(Payload::new("UserAgent.count"));
+    assert!(response.success);
+}
+
+#[test]
+fn invoke_async_future_is_send() {
+    fn assert_send<T: Send>(_: &T) {}
+    let context = context();
+    let future = context.invoke_async(Payload::new("UserAgent.count"));
+    assert_send(&future);
+    block_on(future);
+}
+
+/// Manual agent telling the index path from the name lookup
+struct IndexedAgent;
+
+impl Agent for IndexedAgent {
+    fn name(&self) -> &'static str {
+        "IndexedAgent"
+    }
+
+    fn operations(&self) -> Vec<OperationDescriptor> {
+        vec![OperationDescriptor::new("run")]
+    }
+
+    fn inject_dependencies(&self, _context: &AxorContext) {}
+
+    fn call_operation(&self, payload: &Payload) -> InvokeResult {
+        InvokeResult::ok(payload.op_name_unchecked(), Some(json!("by name")))
+    }
+
+    fn call_operation_at(&self, _index: usize, payload: &Payload) -> InvokeResult {
+        InvokeResult::ok(payload.op_name_unchecked(), Some(json!("by index")))
+    }
+}
+
+#[test]
+fn invoke_async_uses_the_routing_index() {
+    let context = AxorContext::new();
+    context.register(IndexedAgent);
+    context.init();
+
+    let response = block_on(context.invoke_async(Payload::new("IndexedAgent.run")));
+    assert_eq!(response.data, Some(json!("by index")));
+}
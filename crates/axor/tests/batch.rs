@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread::{self, ThreadId};
+
+use axor::prelude::*;
+use axor::{BatchMode, BatchOptions};
+use futures::executor::block_on;
+use serde_json::json;
+
+#[agent]
+struct MathAgent {
+    calls: AtomicUsize,
+}
+
+#[agent_impl]
+impl MathAgent {
+    #[operation]
+    fn double(&self, value: i64) -> i64 {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        value * 2
+    }
+
+    #[operation]
+    fn fail(&self) -> Result<(), String> {
+        Err("boom".into())
+    }
+
+    #[operation]
+    async fn triple(&self, value: i64) -> i64 {
+        futures::future::ready(()).await;
+        value * 3
+    }
+}
+
+#[agent]
+struct ThreadAgent {
+    threads: Mutex<HashSet<ThreadId>>,
+}
+
+#[agent_impl]
+impl ThreadAgent {
+    #[operation]
+    fn echo(&self, value: usize) -> usize {
+        self.threads.lock().unwrap().insert(thread::current().id());
+        value
+    }
+}
+
+fn context() -> AxorContext {
+    let context = AxorContext::new();
+    context.register(MathAgent::default());
+    context
+}
+
+fn calls(context: &AxorContext) -> usize {
+    context.get::<MathAgent>().unwrap().calls.load(Ordering::SeqCst)
+}
+
+fn payloads() -> Vec<Payload> {
+    vec![
+        Payload::with_data("MathAgent.double", &1),
+        Payload::new("MathAgent.fail").with_id("failing"),
+        Payload::with_data("MathAgent.double", &3),
+    ]
+}
+
+#[test]
+fn results_follow_the_payloads_with_their_ids() {
+    for options in [BatchOptions::sequential(), BatchOptions::parallel()] {
+        let results = context().invoke_batch(payloads(), options);
+
+        let ids: Vec<_> = results.iter().map(|result| result.id.as_deref()).collect();
+        assert_eq!(ids, [None, Some("failing"), None]);
+        assert_eq!(results[0].data, Some(json!(2)));
+        assert!(!results[1].success);
+        assert_eq!(results[2].data, Some(json!(6)));
+    }
+}
+
+#[test]
+fn stop_on_failure_skips_the_remaining_items() {
+    let context = context();
+    let results = context.invoke_batch(payloads(), BatchOptions::sequential().stop_on_failure());
+
+    assert!(results[0].success);
+    assert_eq!(results[1].error.as_ref().unwrap().code, ErrorCode::OperationFailed);
+    assert_eq!(results[2].error.as_ref().unwrap().code, ErrorCode::Skipped);
+    assert_eq!(calls(&context), 1);
+}
+
+#[test]
+fn async_batches_run_async_operations() {
+    let context = context();
+    let mixed = vec![
+        Payload::with_data("MathAgent.triple", &1),
+        Payload::with_data("MathAgent.double", &2),
+    ];
+
+    for options in [BatchOptions::sequential(), BatchOptions::parallel()] {
+        let results = block_on(context.invoke_batch_async(mixed.clone(), options));
+        let data: Vec<_> = results.into_iter().map(|result| result.data).collect();
+        assert_eq!(data, [Some(json!(3)), Some(json!(4))]);
+    }
+
+    let results = block_on(context.invoke_batch_async(
+        payloads(),
+        BatchOptions::sequential().stop_on_failure(),
+    ));
+    assert_eq!(results[2].error.as_ref().unwrap().code, ErrorCode::Skipped);
+}
+
+#[test]
+fn options_are_deserializable() {
+    let options: BatchOptions =
+        serde_json::from_value(json!({ "mode": "parallel", "stop_on_failure": true })).unwrap();
+    assert_eq!(options, BatchOptions::parallel().stop_on_failure());
+    assert_eq!(
+        serde_json::from_value::<BatchOptions>(json!({})).unwrap().mode,
+        BatchMode::Sequential
+    );
+    let options: BatchOptions =
+        serde_json::from_value(json!({ "mode": "parallel", "concurrency": 8 })).unwrap();
+    assert_eq!(options, BatchOptions::parallel().with_concurrency(8));
+}
+
+#[test]
+fn parallel_batches_run_on_a_bounded_number_of_threads() {
+    let context = AxorContext::new();
+    context.register(ThreadAgent::default());
+    let payloads = (0..500)
+        .map(|i| Payload::with_data("ThreadAgent.echo", &i))
+        .collect();
+
+    let results = context.invoke_batch(payloads, BatchOptions::parallel().with_concurrency(4));
+    let data: Vec<_> = results.into_iter().map(|result| result.data).collect();
+    assert_eq!(data, (0..500).map(|i| Some(json!(i))).collect::<Vec<_>>());
+
+    let threads = context.resolve::<ThreadAgent>().threads.lock().unwrap().len();
+    assert!(threads <= 4, "{} threads", threads);
+}
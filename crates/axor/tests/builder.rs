@@ -0,0 +1,150 @@
+use std::sync::Arc;
+use std::thread;
+
+use axor::prelude::*;
+use axor::{BuildError, MissingDependency};
+use serde_json::json;
+
+trait Clock: Send + Sync {
+    fn now(&self) -> u64;
+}
+
+struct FixedClock;
+
+impl Clock for FixedClock {
+    fn now(&self) -> u64 {
+        42
+    }
+}
+
+struct Config {
+    prefix: String,
+}
+
+#[agent]
+struct TimeAgent {
+    clock: Inject<Arc<dyn Clock>>,
+    config: Inject<Config>,
+}
+
+#[agent_impl]
+impl TimeAgent {
+    #[operation]
+    fn now(&self) -> String {
+        format!("{}{}", self.config.resolve().prefix, self.clock.resolve().now())
+    }
+}
+
+#[agent(name = "TimeAgent")]
+struct OtherTimeAgent;
+
+#[agent_impl]
+impl OtherTimeAgent {}
+
+fn config() -> Config {
+    Config {
+        prefix: "t=".into(),
+    }
+}
+
+#[test]
+fn build_injects_agents() {
+    let context = AxorContext::builder()
+        .agent(TimeAgent::default())
+        .service::<Arc<dyn Clock>>(Arc::new(FixedClock))
+        .service(config())
+        .build()
+        .unwrap();
+
+    assert_eq!(context.resolve::<TimeAgent>().now(), "t=42");
+    let response = context.invoke(Payload::new("TimeAgent.now"));
+    assert_eq!(response.data, Some(json!("t=42")));
+}
+
+#[test]
+fn build_reports_missing_dependencies() {
+    let error = AxorContext::builder()
+        .agent(TimeAgent::default())
+        .build()
+        .err()
+        .unwrap();
+
+    let BuildError::InvalidDependencies(invalid) = &error else {
+        panic!("unexpected error: {}", error);
+    };
+    assert_eq!(
+        invalid.missing,
+        vec![
+            MissingDependency {
+                agent: "TimeAgent".into(),
+                field: "clock",
+                name: None,
+                type_name: std::any::type_name::<Arc<dyn Clock>>(),
+            },
+            MissingDependency {
+                agent: "TimeAgent".into(),
+                field: "config",
+                name: None,
+                type_name: std::any::type_name::<Config>(),
+            },
+        ]
+    );
+    assert!(error.to_string().contains("TimeAgent.config requires"));
+}
+
+#[test]
+fn build_rejects_duplicate_routes() {
+    let error = AxorContext::builder()
+        .agent(TimeAgent::default())
+        .agent(OtherTimeAgent)
+        .service::<Arc<dyn Clock>>(Arc::new(FixedClock))
+        .service(config())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        error,
+        BuildError::DuplicateRoute {
+            route: "TimeAgent".into()
+        }
+    );
+}
+
+#[test]
+fn clones_share_the_context() {
+    let context = AxorContext::builder()
+        .service(config())
+        .build()
+        .unwrap();
+
+    let handle = context.clone();
+    handle.register_service::<Arc<dyn Clock>>(Arc::new(FixedClock));
+    assert!(context.get_service::<Arc<dyn Clock>>().is_some());
+}
+
+#[test]
+fn concurrent_invocations_and_registrations() {
+    let context = AxorContext::builder()
+        .agent(TimeAgent::default())
+        .service::<Arc<dyn Clock>>(Arc::new(FixedClock))
+        .service(config())
+        .build()
+        .unwrap();
+
+    let invokers: Vec<_> = (0..4)
+        .map(|_| {
+            let context = context.clone();
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    assert!(context.invoke(Payload::new("TimeAgent.now")).success);
+                }
+            })
+        })
+        .collect();
+    for i in 0..100 {
+        context.register_service(i as u64);
+    }
+    for invoker in invokers {
+        invoker.join().unwrap();
+    }
+}
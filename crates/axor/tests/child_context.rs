@@ -0,0 +1,127 @@
+use axor::prelude::*;
+use serde_json::json;
+
+#[interface]
+trait Clock: Send + Sync {
+    fn now(&self) -> u64;
+}
+
+struct FixedClock(u64);
+
+impl Clock for FixedClock {
+    fn now(&self) -> u64 {
+        self.0
+    }
+}
+
+#[agent]
+struct TimeAgent {
+    clock: Inject<dyn Clock>,
+}
+
+#[agent_impl]
+impl TimeAgent {
+    #[operation]
+    fn now(&self) -> u64 {
+        self.clock.resolve().now()
+    }
+}
+
+#[agent]
+struct EchoAgent;
+
+#[agent_impl]
+impl EchoAgent {
+    #[operation]
+    fn echo(&self, value: String) -> String {
+        value
+    }
+}
+
+fn parent() -> AxorContext {
+    AxorContext::builder()
+        .agent(TimeAgent::default())
+        .agent(EchoAgent)
+        .service_as::<dyn Clock, _>(FixedClock(1))
+        .service(String::from("shared"))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn children_inherit_agents_and_services() {
+    let parent = parent();
+    let child = parent.child();
+
+    assert_eq!(child.resolve::<String>().as_str(), "shared");
+    assert!(child.get::<EchoAgent>().is_some());
+    let result = child.invoke(Payload::with_data("EchoAgent.echo", &json!("hi")));
+    assert_eq!(result.data, Some(json!("hi")));
+}
+
+#[test]
+fn children_override_registrations() {
+    let parent = parent();
+    let child = parent.child();
+    child.register_as::<dyn Clock, _>(FixedClock(2));
+    child.register(TimeAgent::default());
+    child.init();
+
+    let result = child.invoke(Payload::new("TimeAgent.now"));
+    assert_eq!(result.data, Some(json!(2)));
+    let result = parent.invoke(Payload::new("TimeAgent.now"));
+    assert_eq!(result.data, Some(json!(1)));
+    assert_eq!(parent.resolve::<dyn Clock>().now(), 1);
+}
+
+#[test]
+fn child_validation_sees_parent_services() {
+    let parent = parent();
+    let child = parent.child();
+    child.register(TimeAgent::default());
+    assert!(child.validate().is_ok());
+
+    let orphan = AxorContext::new().child();
+    orphan.register(TimeAgent::default());
+    assert!(orphan.validate().is_err());
+}
+
+#[test]
+fn manifest_merges_parent_and_child_agents() {
+    let parent = parent();
+    let child = parent.child();
+    child.register(TimeAgent::default());
+    child.init();
+
+    let routes: Vec<_> = child
+        .manifest()
+        .agents
+        .into_iter()
+        .map(|agent| agent.route)
+        .collect();
+    assert_eq!(routes, vec!["TimeAgent", "EchoAgent"]);
+}
+
+#[test]
+fn unknown_agents_still_fail() {
+    let child = parent().child();
+    let result = child.invoke(Payload::new("MissingAgent.run"));
+    assert_eq!(result.error.unwrap().code, ErrorCode::AgentNotFound);
+}
+
+struct Timestamp(u64);
+
+#[test]
+fn inherited_singletons_are_built_by_their_owner() {
+    let parent = parent();
+    parent.register_factory(ServiceLifetime::Singleton, |context| {
+        Timestamp(context.resolve::<dyn Clock>().now())
+    });
+    let child = parent.child();
+    child.register_as::<dyn Clock, _>(FixedClock(2));
+
+    // Resolved from the child first: the child's clock must not leak into the parent's singleton
+    assert_eq!(child.resolve::<Timestamp>().0, 1);
+    assert_eq!(parent.resolve::<Timestamp>().0, 1);
+    assert_eq!(parent.child().resolve::<Timestamp>().0, 1);
+}
@@ -0,0 +1,101 @@
+use axor::prelude::*;
+use axor::RegisterError;
+use serde_json::json;
+
+#[agent(name = "users")]
+struct UserAgent;
+
+#[agent_impl]
+impl UserAgent {
+    #[operation]
+    fn count(&self) -> u32 {
+        1
+    }
+}
+
+#[agent(name = "users", version = "2")]
+struct UserAgentV2;
+
+#[agent_impl]
+impl UserAgentV2 {
+    #[operation]
+    fn count(&self) -> u32 {
+        2
+    }
+}
+
+#[agent(name = "users")]
+struct OtherUserAgent;
+
+#[agent_impl]
+impl OtherUserAgent {}
+
+fn count(context: &AxorContext, name: &str) -> InvokeResult {
+    context.invoke(Payload::new(name))
+}
+
+#[test]
+fn unregistered_agents_are_gone() {
+    let context = AxorContext::new();
+    context.register(UserAgent);
+    assert!(context.unregister::<UserAgent>().is_some());
+
+    let error = count(&context, "users.count").error.unwrap();
+    assert_eq!(error.code, ErrorCode::AgentNotFound);
+    assert!(context.get::<UserAgent>().is_none());
+    assert!(context.get_service::<UserAgent>().is_none());
+    assert!(context.unregister::<UserAgent>().is_none());
+}
+
+#[test]
+fn disabled_agents_report_a_dedicated_error() {
+    let context = AxorContext::new();
+    context.register(UserAgent);
+    assert!(context.disable("users"));
+
+    let error = count(&context, "users.count").error.unwrap();
+    assert_eq!(error.code, ErrorCode::AgentDisabled);
+    assert_eq!(error.message, "Agent `users` is disabled");
+    assert!(context.get::<UserAgent>().is_some());
+    assert!(context.manifest().agents[0].disabled);
+
+    assert!(context.enable("users"));
+    assert_eq!(count(&context, "users.count").data, Some(json!(1)));
+    assert!(!context.disable("orders"));
+}
+
+#[test]
+fn disabling_a_version_falls_back_to_the_others() {
+    let context = AxorContext::new();
+    context.register(UserAgentV2);
+    context.register(UserAgent);
+    assert!(context.disable("users@2"));
+
+    assert_eq!(count(&context, "users.count").data, Some(json!(1)));
+    let error = count(&context, "users@2.count").error.unwrap();
+    assert_eq!(error.code, ErrorCode::AgentDisabled);
+}
+
+#[test]
+fn duplicate_routes_are_rejected() {
+    let context = AxorContext::new();
+    context.register(UserAgent);
+
+    assert_eq!(
+        context.try_register(OtherUserAgent),
+        Err(RegisterError::DuplicateRoute {
+            route: "users".into()
+        })
+    );
+    // re-registering the same type replaces it
+    assert!(context.try_register(UserAgent).is_ok());
+    assert_eq!(context.manifest().agents.len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Another agent is already registered as `users`")]
+fn register_panics_on_duplicate_routes() {
+    let context = AxorContext::new();
+    context.register(UserAgent);
+    context.register(OtherUserAgent);
+}
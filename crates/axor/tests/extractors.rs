@@ -0,0 +1,141 @@
+use axor::prelude::*;
+use futures::executor::block_on;
+use serde::Deserialize;
+use serde_json::json;
+
+struct Tenant;
+
+impl MetaKey for Tenant {
+    const KEY: &'static str = "tenant";
+}
+
+#[interface]
+trait Clock: Send + Sync {
+    fn now(&self) -> u64;
+}
+
+struct FixedClock;
+
+impl Clock for FixedClock {
+    fn now(&self) -> u64 {
+        42
+    }
+}
+
+#[derive(Deserialize)]
+struct Order {
+    item: String,
+}
+
+/// Custom extractor: the `x-locale` metadata, `en` by default.
+struct Locale(String);
+
+impl<'a> FromInvocation<'a> for Locale {
+    fn from_invocation(invocation: &'a Invocation<'a>, _position: usize) -> Result<Self, InvokeError> {
+        let locale = invocation.payload().metadata("x-locale").unwrap_or("en");
+        Ok(Locale(locale.to_string()))
+    }
+}
+
+#[agent]
+struct ShopAgent;
+
+#[agent_impl]
+impl ShopAgent {
+    #[operation]
+    fn order(&self, tenant: Meta<Tenant>, Json(order): Json<Order>) -> String {
+        format!("{}:{}", &*tenant, order.item)
+    }
+
+    #[operation]
+    fn greet(&self, Locale(locale): Locale, name: Option<String>) -> String {
+        format!("{}:{}", locale, name.unwrap_or_default())
+    }
+
+    #[operation]
+    fn echo(&self, Raw(payload): Raw) -> String {
+        payload.name.clone()
+    }
+
+    #[operation]
+    fn now(&self, clock: Injected<dyn Clock>) -> u64 {
+        clock.now()
+    }
+
+    #[operation]
+    async fn quantity(&self, Raw(payload): Raw<'_>, item: String, count: u32) -> String {
+        futures::future::ready(()).await;
+        format!("{} {} x{}", payload.name, item, count)
+    }
+}
+
+fn context() -> AxorContext {
+    let context = AxorContext::new();
+    context.register(ShopAgent);
+    context.register_as::<dyn Clock, _>(FixedClock);
+    context
+}
+
+fn invoke(payload: Payload) -> InvokeResult {
+    context().invoke(payload)
+}
+
+#[test]
+fn json_and_meta_parameters() {
+    let payload = Payload::with_data("ShopAgent.order", &json!({ "item": "tea" }))
+        .with_metadata("tenant", "acme");
+    assert_eq!(invoke(payload).data, Some(json!("acme:tea")));
+
+    let error = invoke(Payload::with_data("ShopAgent.order", &json!({ "item": "tea" })))
+        .error
+        .unwrap();
+    assert_eq!(error.code, ErrorCode::InvalidInput);
+    assert_eq!(error.message, "Missing metadata `tenant`");
+}
+
+#[test]
+fn custom_extractors_and_optional_bodies() {
+    let payload = Payload::with_data("ShopAgent.greet", &"Ada").with_metadata("x-locale", "fr");
+    assert_eq!(invoke(payload).data, Some(json!("fr:Ada")));
+    assert_eq!(invoke(Payload::new("ShopAgent.greet")).data, Some(json!("en:")));
+}
+
+#[test]
+fn raw_and_injected_parameters() {
+    assert_eq!(
+        invoke(Payload::new("ShopAgent.echo")).data,
+        Some(json!("ShopAgent.echo"))
+    );
+    assert_eq!(invoke(Payload::new("ShopAgent.now")).data, Some(json!(42)));
+
+    let error = AxorContext::new();
+    error.register(ShopAgent);
+    let error = error.invoke(Payload::new("ShopAgent.now")).error.unwrap();
+    assert_eq!(error.code, ErrorCode::Internal);
+}
+
+#[test]
+fn extractors_leave_inputs_decoded_by_name_or_position() {
+    let context = context();
+    for data in [json!({ "item": "tea", "count": 2 }), json!(["tea", 2])] {
+        let payload = Payload::with_data("ShopAgent.quantity", &data);
+        let result = block_on(context.invoke_async(payload));
+        assert_eq!(result.data, Some(json!("ShopAgent.quantity tea x2")));
+    }
+}
+
+#[test]
+fn only_inputs_are_listed_in_the_manifest() {
+    let manifest = context().manifest();
+    let params = |name: &str| -> Vec<&str> {
+        let operation = manifest.agents[0]
+            .operations
+            .iter()
+            .find(|op| op.name == name)
+            .unwrap();
+        operation.params.iter().map(|param| param.name).collect()
+    };
+    assert_eq!(params("order"), ["order"]);
+    assert_eq!(params("greet"), ["name"]);
+    assert_eq!(params("quantity"), ["item", "count"]);
+}
@@ -86,7 +86,7 @@ fn hello_with_macros() {
 
     // Direct invocation with type safety
     let agent = context.resolve::<PrintAgent>();
-    let _ = agent.print_message("Hello world".to_string());
+    agent.print_message("Hello world".to_string());
 
     let agent = context.resolve::<WorkflowAgent>();
     let result = agent.run();
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use axor::{Agent, AxorContext, Inject, InvokeResult, OperationDescriptor, Payload};
+use axor::{Agent, AxorContext, Inject, InvokeError, InvokeResult, OperationDescriptor, Payload};
 
 #[derive(Default)]
 struct HelloAgent {
@@ -30,7 +30,7 @@ impl Agent for HelloAgent {
     }
 
     fn operations(&self) -> Vec<crate::OperationDescriptor> {
-        vec![OperationDescriptor { name: "hello" }]
+        vec![OperationDescriptor::new("hello")]
     }
 
     fn inject_dependencies(&self, context: &AxorContext) {
@@ -47,18 +47,13 @@ impl Agent for HelloAgent {
                 Some(json_data)
             }
             _ => {
-                return InvokeResult {
-                    operation: payload.name.to_string(),
-                    success: true,
-                    data: None,
-                }
+                return InvokeResult::err(
+                    payload.name.to_string(),
+                    InvokeError::operation_not_found(&payload.name),
+                )
             }
         };
-        InvokeResult {
-            operation: payload.name.to_string(),
-            success: true,
-            data: return_value,
-        }
+        InvokeResult::ok(payload.name.to_string(), return_value)
     }
 }
 
@@ -76,9 +71,7 @@ impl Agent for PrintAgent {
     }
 
     fn operations(&self) -> Vec<crate::OperationDescriptor> {
-        vec![OperationDescriptor {
-            name: "print_message",
-        }]
+        vec![OperationDescriptor::new("print_message").with_param("message", "&str")]
     }
     fn inject_dependencies(&self, _context: &AxorContext) {}
 
@@ -86,31 +79,20 @@ impl Agent for PrintAgent {
         let return_value = match payload.op_name_unchecked() {
             "print_message" => {
                 let message: String = match payload.input_as() {
-                    Some(input) => input,
-                    None => {
-                        return InvokeResult {
-                            operation: payload.name.to_string(),
-                            success: false,
-                            data: None,
-                        }
-                    }
+                    Ok(input) => input,
+                    Err(error) => return InvokeResult::err(payload.name.to_string(), error),
                 };
                 self.print_message(message.as_ref());
                 None
             }
             _ => {
-                return InvokeResult {
-                    operation: payload.name.to_string(),
-                    success: true,
-                    data: None,
-                }
+                return InvokeResult::err(
+                    payload.name.to_string(),
+                    InvokeError::operation_not_found(&payload.name),
+                )
             }
         };
-        InvokeResult {
-            operation: payload.name.to_string(),
-            success: true,
-            data: return_value,
-        }
+        InvokeResult::ok(payload.name.to_string(), return_value)
     }
 }
 
@@ -136,7 +118,7 @@ impl Agent for WorkflowAgent {
     }
 
     fn operations(&self) -> Vec<crate::OperationDescriptor> {
-        vec![OperationDescriptor { name: "run" }]
+        vec![OperationDescriptor::new("run")]
     }
 
     fn inject_dependencies(&self, context: &AxorContext) {
@@ -152,18 +134,13 @@ impl Agent for WorkflowAgent {
                 Some(json_data)
             }
             _ => {
-                return InvokeResult {
-                    operation: payload.name.to_string(),
-                    success: true,
-                    data: None,
-                }
+                return InvokeResult::err(
+                    payload.name.to_string(),
+                    InvokeError::operation_not_found(&payload.name),
+                )
             }
         };
-        InvokeResult {
-            operation: payload.name.to_string(),
-            success: true,
-            data: return_value,
-        }
+        InvokeResult::ok(payload.name.to_string(), return_value)
     }
 }
 
@@ -0,0 +1,152 @@
+use std::sync::Mutex;
+
+use axor::prelude::*;
+use serde_json::json;
+
+struct Config {
+    greeting: &'static str,
+}
+
+#[interface]
+trait Feature: Send + Sync {
+    fn enabled(&self) -> bool;
+}
+
+struct Toggle(bool);
+
+impl Feature for Toggle {
+    fn enabled(&self) -> bool {
+        self.0
+    }
+}
+
+#[agent]
+struct GreeterAgent {
+    config: Inject<Config>,
+    feature: Inject<dyn Feature>,
+    changes: Mutex<Vec<&'static str>>,
+}
+
+#[agent_impl]
+impl GreeterAgent {
+    #[operation]
+    fn greet(&self) -> String {
+        let greeting = self.config.resolve().greeting;
+        if self.feature.resolve().enabled() {
+            greeting.to_uppercase()
+        } else {
+            greeting.to_string()
+        }
+    }
+
+    #[on_service_change]
+    fn reload(&self, dependency: &Dependency) {
+        self.changes.lock().unwrap().push(dependency.field);
+    }
+}
+
+fn context() -> AxorContext {
+    AxorContext::builder()
+        .agent(GreeterAgent::default())
+        .service(Config { greeting: "hello" })
+        .service_as::<dyn Feature, _>(Toggle(false))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn replaced_services_reach_injected_fields() {
+    let context = context();
+    let greet = || context.invoke(Payload::new("GreeterAgent.greet")).data;
+    assert_eq!(greet(), Some(json!("hello")));
+
+    context.replace_service(Config { greeting: "hi" });
+    assert_eq!(greet(), Some(json!("hi")));
+
+    context.replace_as::<dyn Feature, _>(Toggle(true));
+    assert_eq!(greet(), Some(json!("HI")));
+}
+
+#[test]
+fn dependents_are_notified() {
+    let context = context();
+    context.replace_service(Config { greeting: "hi" });
+    context.replace_as::<dyn Feature, _>(Toggle(true));
+    context.replace_service(42u32);
+
+    let agent = context.resolve::<GreeterAgent>();
+    assert_eq!(*agent.changes.lock().unwrap(), vec!["config", "feature"]);
+}
+
+#[test]
+fn register_service_does_not_propagate() {
+    let context = context();
+    context.register_service(Config { greeting: "hi" });
+
+    assert_eq!(context.resolve::<GreeterAgent>().greet(), "hello");
+    assert_eq!(context.resolve::<Config>().greeting, "hi");
+}
+
+#[test]
+fn replacing_in_a_child_leaves_the_parent_alone() {
+    let parent = context();
+    let child = parent.child();
+    child.replace_service(Config { greeting: "hi" });
+
+    assert_eq!(child.resolve::<Config>().greeting, "hi");
+    assert_eq!(parent.resolve::<Config>().greeting, "hello");
+}
+
+#[agent]
+struct FeatureAgent {
+    feature: Inject<dyn Feature>,
+    changes: Mutex<Vec<&'static str>>,
+}
+
+#[agent_impl]
+impl FeatureAgent {
+    #[operation]
+    fn enabled(&self) -> bool {
+        self.feature.resolve().enabled()
+    }
+
+    #[on_service_change]
+    fn reload(&self, dependency: &Dependency) {
+        self.changes.lock().unwrap().push(dependency.field);
+    }
+}
+
+#[test]
+fn replaced_services_reach_exposed_interfaces() {
+    let context = AxorContext::builder()
+        .agent(FeatureAgent::default())
+        .service(Toggle(false))
+        .expose::<dyn Feature, Toggle>()
+        .build()
+        .unwrap();
+
+    context.replace_service(Toggle(true));
+    assert!(context.resolve::<dyn Feature>().enabled());
+    assert_eq!(
+        context.invoke(Payload::new("FeatureAgent.enabled")).data,
+        Some(json!(true))
+    );
+
+    let agent = context.resolve::<FeatureAgent>();
+    assert_eq!(*agent.changes.lock().unwrap(), vec!["feature"]);
+}
+
+#[test]
+fn replacing_in_a_child_reaches_its_agents() {
+    let parent = context();
+    let child = parent.child();
+    child.register(GreeterAgent::default());
+    child.init();
+
+    child.replace_service(Config { greeting: "hi" });
+
+    let agent = child.resolve::<GreeterAgent>();
+    assert_eq!(agent.greet(), "hi");
+    assert_eq!(*agent.changes.lock().unwrap(), vec!["config"]);
+    assert_eq!(parent.resolve::<GreeterAgent>().greet(), "hello");
+}
@@ -0,0 +1,102 @@
+use std::sync::{Arc, Mutex};
+
+use axor::prelude::*;
+use serde_json::json;
+
+#[interface]
+trait Logger: Send + Sync {
+    fn log(&self, message: &str);
+}
+
+#[interface]
+trait History: Send + Sync {
+    fn lines(&self) -> Vec<String>;
+}
+
+#[derive(Default)]
+struct MemoryLogger {
+    lines: Mutex<Vec<String>>,
+}
+
+impl Logger for MemoryLogger {
+    fn log(&self, message: &str) {
+        self.lines.lock().unwrap().push(message.to_string());
+    }
+}
+
+impl History for MemoryLogger {
+    fn lines(&self) -> Vec<String> {
+        self.lines.lock().unwrap().clone()
+    }
+}
+
+#[agent]
+struct GreeterAgent {
+    logger: Inject<dyn Logger>,
+    history: Inject<dyn History>,
+}
+
+#[agent_impl]
+impl GreeterAgent {
+    #[operation]
+    fn greet(&self, name: String) -> Vec<String> {
+        self.logger.resolve().log(&format!("hello {}", name));
+        self.history.resolve().lines()
+    }
+}
+
+#[test]
+fn register_as_resolves_trait_objects() {
+    let context = AxorContext::new();
+    context.register_as::<dyn Logger, _>(MemoryLogger::default());
+
+    let logger: Arc<dyn Logger> = context.resolve::<dyn Logger>();
+    logger.log("direct");
+    assert!(context.get_service::<dyn History>().is_none());
+    assert!(context.get_service::<MemoryLogger>().is_none());
+}
+
+#[test]
+fn one_service_behind_several_interfaces() {
+    let context = AxorContext::builder()
+        .agent(GreeterAgent::default())
+        .service(MemoryLogger::default())
+        .expose::<dyn Logger, MemoryLogger>()
+        .expose::<dyn History, MemoryLogger>()
+        .build()
+        .unwrap();
+
+    context.invoke(Payload::with_data("GreeterAgent.greet", &json!("ann")));
+    let result = context.invoke(Payload::with_data("GreeterAgent.greet", &json!("bob")));
+    assert_eq!(result.data, Some(json!(["hello ann", "hello bob"])));
+    assert_eq!(context.resolve::<MemoryLogger>().lines().len(), 2);
+}
+
+#[test]
+fn exposed_factories_share_their_lifetime() {
+    let context = AxorContext::new();
+    context.register_factory(ServiceLifetime::Singleton, |_| MemoryLogger::default());
+    context.expose::<dyn Logger, MemoryLogger>();
+
+    context.resolve::<dyn Logger>().log("once");
+    assert_eq!(context.resolve::<MemoryLogger>().lines(), vec!["once"]);
+}
+
+#[test]
+#[should_panic(expected = "register it first")]
+fn expose_requires_the_concrete_service() {
+    AxorContext::new().expose::<dyn Logger, MemoryLogger>();
+}
+
+#[test]
+fn builder_reports_unexposable_services() {
+    let error = AxorContext::builder()
+        .expose::<dyn Logger, MemoryLogger>()
+        .service(MemoryLogger::default())
+        .build()
+        .err()
+        .unwrap();
+
+    assert!(matches!(error, axor::BuildError::NotExposable { .. }));
+    assert!(error.to_string().contains("register it first"));
+}
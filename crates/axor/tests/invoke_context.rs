@@ -0,0 +1,135 @@
+use axor::prelude::*;
+use axor::{Cancellation, Principal};
+use futures::executor::block_on;
+use serde_json::json;
+
+#[agent]
+struct GreeterAgent;
+
+#[agent_impl]
+impl GreeterAgent {
+    #[operation]
+    fn greet(&self, name: String) -> String {
+        let invocation = InvokeContext::current().unwrap();
+        match invocation.metadata("locale") {
+            Some("fr") => format!("Bonjour {}", name),
+            _ => format!("Hello {}", name),
+        }
+    }
+
+    #[operation]
+    fn whoami(&self, invocation: &InvokeContext) -> Option<String> {
+        Some(invocation.principal()?.id.clone())
+    }
+
+    /// The context parameter may come anywhere, the others still decode by name or position
+    #[operation]
+    fn repeat(&self, word: String, invocation: &InvokeContext, times: usize) -> Vec<String> {
+        if invocation.is_cancelled() {
+            return Vec::new();
+        }
+        vec![word; times]
+    }
+
+    #[operation]
+    async fn agents(&self, invocation: &InvokeContext) -> usize {
+        futures::future::ready(()).await;
+        invocation.context().map_or(0, |context| context.manifest().agents.len())
+    }
+
+    #[operation]
+    async fn request_id(&self) -> Option<String> {
+        futures::future::ready(()).await;
+        InvokeContext::current()?.id().map(str::to_string)
+    }
+}
+
+fn context() -> AxorContext {
+    let context = AxorContext::new();
+    context.register(GreeterAgent);
+    context
+}
+
+#[test]
+fn operations_read_the_payload_metadata() {
+    let context = context();
+    let payload = Payload::with_data("GreeterAgent.greet", &"Ada").with_metadata("locale", "fr");
+    assert_eq!(context.invoke(payload).data, Some(json!("Bonjour Ada")));
+
+    let payload = Payload::with_data("GreeterAgent.greet", &"Ada");
+    assert_eq!(context.invoke(payload).data, Some(json!("Hello Ada")));
+    assert!(InvokeContext::current().is_none());
+}
+
+#[test]
+fn ids_are_echoed_and_readable_across_awaits() {
+    let context = context();
+    let payload = Payload::new("GreeterAgent.request_id").with_id("req-1");
+    let result = block_on(context.invoke_async(payload));
+
+    assert_eq!(result.id.as_deref(), Some("req-1"));
+    assert_eq!(result.data, Some(json!("req-1")));
+}
+
+#[test]
+fn metadata_is_part_of_the_wire_format() {
+    let payload: Payload = serde_json::from_value(json!({
+        "id": "42",
+        "name": "GreeterAgent.greet",
+        "data": "Ada",
+        "metadata": { "locale": "fr" },
+        "success": true
+    }))
+    .unwrap();
+    assert_eq!(payload.metadata("locale"), Some("fr"));
+
+    let result = context().invoke(payload);
+    let result = serde_json::to_value(&result).unwrap();
+    assert_eq!(result["id"], "42");
+    assert_eq!(result["data"], "Bonjour Ada");
+}
+
+#[test]
+fn operations_declare_an_invoke_context_parameter() {
+    let context = context();
+    let payload = Payload::new("GreeterAgent.whoami")
+        .with_principal(Principal::new("ada").with_role("admin"));
+    assert_eq!(context.invoke(payload).data, Some(json!("ada")));
+    assert_eq!(
+        context.invoke(Payload::new("GreeterAgent.whoami")).data,
+        Some(json!(null))
+    );
+
+    let payload = Payload::with_data("GreeterAgent.repeat", &json!(["hi", 2]));
+    assert_eq!(context.invoke(payload).data, Some(json!(["hi", "hi"])));
+
+    let result = block_on(context.invoke_async(Payload::new("GreeterAgent.agents")));
+    assert_eq!(result.data, Some(json!(1)));
+}
+
+#[test]
+fn cancellation_is_shared_with_the_runtime() {
+    let cancellation = Cancellation::new();
+    let payload = Payload::with_data("GreeterAgent.repeat", &json!({ "word": "hi", "times": 3 }))
+        .with_cancellation(cancellation.clone());
+    cancellation.cancel();
+
+    assert_eq!(context().invoke(payload).data, Some(json!([])));
+}
+
+#[test]
+fn invoke_context_parameters_are_not_part_of_the_input() {
+    let manifest = context().manifest();
+    let repeat = manifest.agents[0]
+        .operations
+        .iter()
+        .find(|op| op.name == "repeat")
+        .unwrap();
+    let params: Vec<_> = repeat.params.iter().map(|param| param.name).collect();
+    assert_eq!(params, ["word", "times"]);
+
+    // direct calls pass an empty context
+    let invocation = InvokeContext::default();
+    assert_eq!(GreeterAgent.repeat("hi".into(), &invocation, 1), ["hi"]);
+    assert!(invocation.context().is_none());
+}
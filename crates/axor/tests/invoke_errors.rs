@@ -0,0 +1,69 @@
+use axor::prelude::*;
+
+#[agent]
+struct UserAgent;
+
+#[agent_impl]
+impl UserAgent {
+    #[operation]
+    fn greet(&self, name: String) -> String {
+        format!("Hello, {}!", name)
+    }
+}
+
+fn context() -> AxorContext {
+    let context = AxorContext::new();
+    context.register(UserAgent);
+    context.init();
+    context
+}
+
+#[test]
+fn invoke_success_has_no_error() {
+    let response = context().invoke(Payload::with_data("UserAgent.greet", &"Bob"));
+    assert!(response.success);
+    assert!(response.error.is_none());
+    assert_eq!(response.data, Some(serde_json::json!("Hello, Bob!")));
+}
+
+#[test]
+fn invoke_invalid_name() {
+    let response = context().invoke(Payload::new("UserAgent"));
+    assert!(!response.success);
+    assert_eq!(response.error.unwrap().code, ErrorCode::InvalidName);
+}
+
+#[test]
+fn invoke_unknown_agent() {
+    let response = context().invoke(Payload::new("MissingAgent.greet"));
+    assert!(!response.success);
+    let error = response.error.unwrap();
+    assert_eq!(error.code, ErrorCode::AgentNotFound);
+    assert!(error.message.contains("MissingAgent"));
+}
+
+#[test]
+fn invoke_unknown_operation() {
+    let response = context().invoke(Payload::new("UserAgent.missing"));
+    assert!(!response.success);
+    assert_eq!(response.error.unwrap().code, ErrorCode::OperationNotFound);
+}
+
+#[test]
+fn invoke_invalid_input() {
+    let response = context().invoke(Payload::with_data("UserAgent.greet", &42));
+    assert!(!response.success);
+    assert_eq!(response.error.unwrap().code, ErrorCode::InvalidInput);
+
+    let response = context().invoke(Payload::new("UserAgent.greet"));
+    assert_eq!(response.error.unwrap().code, ErrorCode::InvalidInput);
+}
+
+#[test]
+fn error_serializes_with_stable_code() {
+    let response = context().invoke(Payload::new("UserAgent.missing"));
+    let json = serde_json::to_value(&response).unwrap();
+    assert_eq!(json["success"], false);
+    assert_eq!(json["error"]["code"], "operation_not_found");
+    assert!(json["error"].get("details").is_none());
+}
@@ -0,0 +1,155 @@
+use std::sync::Mutex;
+
+use axor::prelude::*;
+use axor::{LifecycleError, LifecycleHook};
+use futures::executor::block_on;
+
+#[derive(Default)]
+struct Events(Mutex<Vec<String>>);
+
+impl Events {
+    fn push(&self, event: &str) {
+        self.0.lock().unwrap().push(event.to_string());
+    }
+
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+#[agent]
+struct DatabaseAgent {
+    events: Inject<Events>,
+}
+
+#[agent_impl]
+impl DatabaseAgent {
+    #[on_init]
+    fn open_pool(&self) {
+        self.events.resolve().push("database.init");
+    }
+
+    #[on_start]
+    fn on_start(&self) {
+        self.events.resolve().push("database.start");
+    }
+
+    #[on_stop]
+    fn close_pool(&self) -> Result<(), String> {
+        self.events.resolve().push("database.stop");
+        Ok(())
+    }
+}
+
+#[agent]
+struct ApiAgent {
+    events: Inject<Events>,
+    database: Inject<DatabaseAgent>,
+}
+
+#[agent_impl]
+impl ApiAgent {
+    #[on_start]
+    fn listen(&self) {
+        let _ = self.database.resolve();
+        self.events.resolve().push("api.start");
+    }
+
+    #[on_stop]
+    fn shutdown(&self) {
+        self.events.resolve().push("api.stop");
+    }
+}
+
+#[agent]
+struct BrokenAgent {
+    database: Inject<DatabaseAgent>,
+}
+
+#[agent_impl]
+impl BrokenAgent {
+    #[on_start]
+    fn connect(&self) -> Result<(), String> {
+        let _ = self.database.resolve();
+        Err("connection refused".into())
+    }
+}
+
+#[agent]
+struct CacheAgent {
+    events: Inject<Events>,
+}
+
+#[agent_impl]
+impl CacheAgent {
+    #[on_start]
+    async fn warm_up(&self) {
+        futures::future::ready(()).await;
+        self.events.resolve().push("cache.start");
+    }
+}
+
+fn events(context: &AxorContext) -> Vec<String> {
+    context.resolve::<Events>().take()
+}
+
+#[test]
+fn hooks_run_in_dependency_order() {
+    // the API is registered first, but depends on the database
+    let context = AxorContext::builder()
+        .agent(ApiAgent::default())
+        .agent(DatabaseAgent::default())
+        .service(Events::default())
+        .build()
+        .unwrap();
+
+    context.start().unwrap();
+    assert_eq!(
+        events(&context),
+        ["database.init", "database.start", "api.start"]
+    );
+
+    context.stop().unwrap();
+    assert_eq!(events(&context), ["api.stop", "database.stop"]);
+}
+
+#[test]
+fn failing_hooks_abort_startup() {
+    let context = AxorContext::builder()
+        .agent(BrokenAgent::default())
+        .agent(DatabaseAgent::default())
+        .service(Events::default())
+        .build()
+        .unwrap();
+
+    let error = context.start().unwrap_err();
+    assert_eq!(error.agent, "BrokenAgent");
+    assert_eq!(error.hook, LifecycleHook::Start);
+    assert_eq!(error.error.code, ErrorCode::OperationFailed);
+    assert_eq!(
+        error.to_string(),
+        "Agent `BrokenAgent` failed in `on_start`: connection refused"
+    );
+    // the database was started, then stopped again
+    assert_eq!(
+        events(&context),
+        ["database.init", "database.start", "database.stop"]
+    );
+}
+
+#[test]
+fn async_hooks_require_start_async() {
+    let context = AxorContext::builder()
+        .agent(CacheAgent::default())
+        .service(Events::default())
+        .build()
+        .unwrap();
+
+    let LifecycleError { hook, error, .. } = context.start().unwrap_err();
+    assert_eq!(hook, LifecycleHook::Start);
+    assert_eq!(error.code, ErrorCode::AsyncOperation);
+
+    block_on(context.start_async()).unwrap();
+    assert_eq!(events(&context), ["cache.start"]);
+    block_on(context.stop_async()).unwrap();
+}
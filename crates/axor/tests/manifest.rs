@@ -0,0 +1,133 @@
+use axor::prelude::*;
+use serde_json::{json, Value};
+
+#[agent]
+struct UserAgent;
+
+#[agent_impl]
+impl UserAgent {
+    #[operation]
+    fn rename(&self, id: u32, name: String) -> anyhow::Result<String> {
+        Ok(format!("{}:{}", id, name))
+    }
+
+    #[operation]
+    fn ping(&self) {}
+}
+
+/// Manages user accounts.
+///
+/// Backed by the accounts database.
+#[agent]
+struct AccountAgent;
+
+#[agent_impl]
+impl AccountAgent {
+    /// Returns the number of accounts.
+    #[operation(tags(admin, "stats"))]
+    fn count(&self) -> u32 {
+        3
+    }
+
+    /// Ignored, the `description` argument wins.
+    #[operation(name = "remove", description = "Removes an account", deprecated)]
+    fn delete_account(&self, id: u32) -> u32 {
+        id
+    }
+
+    #[operation(hidden)]
+    fn reset(&self) -> bool {
+        true
+    }
+}
+
+fn context() -> AxorContext {
+    let context = AxorContext::new();
+    context.register(UserAgent);
+    context.register(AccountAgent);
+    context
+}
+
+fn agent(manifest: &Value, name: &str) -> Value {
+    manifest["agents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|agent| agent["name"] == name)
+        .unwrap()
+        .clone()
+}
+
+fn operation(agent: &Value, name: &str) -> Option<Value> {
+    agent["operations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|op| op["name"] == name)
+        .cloned()
+}
+
+#[test]
+fn manifest_describes_operations() {
+    let manifest = serde_json::to_value(context().manifest()).unwrap();
+    let agent = agent(&manifest, "UserAgent");
+    assert!(agent.get("description").is_none());
+
+    let rename = operation(&agent, "rename").unwrap();
+    assert_eq!(
+        rename["params"],
+        json!([
+            { "name": "id", "type_name": "u32" },
+            { "name": "name", "type_name": "String" },
+        ])
+    );
+    assert_eq!(rename["output_type"], "String");
+
+    let ping = operation(&agent, "ping").unwrap();
+    assert_eq!(ping["params"], json!([]));
+    assert!(ping.get("output_type").is_none());
+    assert!(ping.get("deprecated").is_none());
+    assert!(ping.get("tags").is_none());
+}
+
+#[test]
+fn manifest_carries_docs_and_attributes() {
+    let manifest = serde_json::to_value(context().manifest()).unwrap();
+    let agent = agent(&manifest, "AccountAgent");
+    assert_eq!(
+        agent["description"],
+        "Manages user accounts.\n\nBacked by the accounts database."
+    );
+
+    let count = operation(&agent, "count").unwrap();
+    assert_eq!(count["description"], "Returns the number of accounts.");
+    assert_eq!(count["tags"], json!(["admin", "stats"]));
+
+    assert!(operation(&agent, "delete_account").is_none());
+    let remove = operation(&agent, "remove").unwrap();
+    assert_eq!(remove["description"], "Removes an account");
+    assert_eq!(remove["deprecated"], true);
+
+    assert!(operation(&agent, "reset").is_none());
+}
+
+#[test]
+fn renamed_operation_is_routed_by_its_name() {
+    let context = context();
+    let response = context.invoke(Payload::with_data("AccountAgent.remove", &7));
+    assert_eq!(response.data, Some(json!(7)));
+
+    let response = context.invoke(Payload::with_data("AccountAgent.delete_account", &7));
+    assert_eq!(response.error.unwrap().code, ErrorCode::OperationNotFound);
+}
+
+#[test]
+fn hidden_operation_is_only_callable_directly() {
+    let context = context();
+    let response = context.invoke(Payload::new("AccountAgent.reset"));
+    assert_eq!(response.error.unwrap().code, ErrorCode::OperationNotFound);
+
+    assert!(context.resolve::<AccountAgent>().reset());
+    let descriptors = context.resolve::<AccountAgent>().operations();
+    assert!(descriptors.iter().any(|op| op.name == "reset" && op.hidden));
+}
@@ -0,0 +1,226 @@
+use std::ops::ControlFlow;
+use std::sync::{Arc, Mutex};
+
+use axor::prelude::*;
+use futures::executor::block_on;
+use serde_json::json;
+
+#[agent(name = "users")]
+struct UserAgent;
+
+#[agent_impl]
+impl UserAgent {
+    #[operation]
+    fn get(&self, id: u32) -> String {
+        format!("user {}", id)
+    }
+
+    #[operation]
+    fn delete(&self, id: u32) -> u32 {
+        id
+    }
+
+    #[operation]
+    async fn count(&self) -> u32 {
+        1
+    }
+}
+
+#[agent(name = "users", version = "2")]
+struct UserAgentV2;
+
+#[agent_impl]
+impl UserAgentV2 {
+    #[operation]
+    fn get(&self, id: u32) -> String {
+        format!("user v2 {}", id)
+    }
+}
+
+/// Records its `before` and `after` calls under `label`.
+struct Trace {
+    label: &'static str,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl Middleware for Trace {
+    fn before(&self, payload: &mut Payload) -> ControlFlow<InvokeResult> {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("{} before {}", self.label, payload.name));
+        ControlFlow::Continue(())
+    }
+
+    fn after(&self, _payload: &Payload, _result: &mut InvokeResult) {
+        self.log.lock().unwrap().push(format!("{} after", self.label));
+    }
+}
+
+struct DenyDelete;
+
+impl Middleware for DenyDelete {
+    fn before(&self, payload: &mut Payload) -> ControlFlow<InvokeResult> {
+        ControlFlow::Break(InvokeResult::err(
+            payload.name.clone(),
+            InvokeError::operation_failed("Forbidden"),
+        ))
+    }
+}
+
+/// Routes `users.get` to version 2 and wraps the output.
+struct Rewrite;
+
+impl Middleware for Rewrite {
+    fn before(&self, payload: &mut Payload) -> ControlFlow<InvokeResult> {
+        payload.name = "users@2.get".into();
+        ControlFlow::Continue(())
+    }
+
+    fn after(&self, _payload: &Payload, result: &mut InvokeResult) {
+        result.data = Some(json!({ "value": result.data.take() }));
+    }
+}
+
+fn context(log: &Arc<Mutex<Vec<String>>>) -> AxorContext {
+    let trace = |label| Trace {
+        label,
+        log: log.clone(),
+    };
+    AxorContext::builder()
+        .agent(UserAgent)
+        .agent(UserAgentV2)
+        .middleware(trace("global"))
+        .agent_middleware("users", trace("users"))
+        .operation_middleware("users.get", trace("get"))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn middlewares_wrap_matching_invocations_in_order() {
+    let log = Arc::default();
+    let context = context(&log);
+
+    let result = context.invoke(Payload::with_data("users.get", &json!(1)));
+    assert_eq!(result.data, Some(json!("user 1")));
+    assert_eq!(
+        *log.lock().unwrap(),
+        [
+            "global before users.get",
+            "users before users.get",
+            "get before users.get",
+            "get after",
+            "users after",
+            "global after",
+        ]
+    );
+
+    // unversioned scopes match every version, the operation scope only `get`
+    log.lock().unwrap().clear();
+    context.invoke(Payload::with_data("users@2.get", &json!(1)));
+    context.invoke(Payload::with_data("users.delete", &json!(1)));
+    assert_eq!(log.lock().unwrap().len(), 6 + 4);
+}
+
+#[test]
+fn middlewares_can_short_circuit() {
+    let log = Arc::default();
+    let context = context(&log);
+    context.register_operation_middleware("users.delete", DenyDelete);
+
+    let error = context
+        .invoke(Payload::with_data("users.delete", &json!(1)))
+        .error
+        .unwrap();
+    assert_eq!(error.message, "Forbidden");
+    // the outer middlewares still see the result
+    assert_eq!(
+        *log.lock().unwrap(),
+        [
+            "global before users.delete",
+            "users before users.delete",
+            "users after",
+            "global after",
+        ]
+    );
+}
+
+#[test]
+fn middlewares_can_rewrite_payloads_and_results() {
+    let context = AxorContext::new();
+    context.register(UserAgent);
+    context.register(UserAgentV2);
+    context.register_operation_middleware("users.get", Rewrite);
+
+    let result = context.invoke(Payload::with_data("users.get", &json!(1)));
+    assert_eq!(result.data, Some(json!({ "value": "user v2 1" })));
+}
+
+#[test]
+fn middlewares_apply_to_async_invocations_and_children() {
+    let log = Arc::default();
+    let context = context(&log);
+    let child = context.child();
+
+    let result = block_on(child.invoke_async(Payload::new("users.count")));
+    assert_eq!(result.data, Some(json!(1)));
+    assert_eq!(
+        *log.lock().unwrap(),
+        [
+            "global before users.count",
+            "users before users.count",
+            "users after",
+            "global after",
+        ]
+    );
+}
+
+#[test]
+fn rewritten_names_select_the_following_middlewares() {
+    let log: Arc<Mutex<Vec<String>>> = Arc::default();
+    let trace = |label| Trace {
+        label,
+        log: log.clone(),
+    };
+    let context = AxorContext::new();
+    context.register(UserAgent);
+    context.register(UserAgentV2);
+    context.register_agent_middleware("users@2", trace("before rewrite"));
+    context.register_operation_middleware("users.get", Rewrite);
+    context.register_agent_middleware("users@2", trace("v2"));
+    context.register_agent_middleware("orders", trace("orders"));
+
+    context.invoke(Payload::with_data("users.get", &json!(1)));
+    assert_eq!(*log.lock().unwrap(), ["v2 before users@2.get", "v2 after"]);
+}
+
+#[test]
+fn unversioned_aliases_meet_the_middlewares_of_their_version() {
+    let log: Arc<Mutex<Vec<String>>> = Arc::default();
+    let context = AxorContext::new();
+    context.register(UserAgentV2);
+    context.register_agent_middleware(
+        "users@2",
+        Trace {
+            label: "v2",
+            log: log.clone(),
+        },
+    );
+    context.register_operation_middleware("users@2.get", DenyDelete);
+
+    // `users` routes to the only version, `users@2`
+    let error = context
+        .invoke(Payload::with_data("users.get", &json!(1)))
+        .error
+        .unwrap();
+    assert_eq!(error.message, "Forbidden");
+    let error = block_on(context.invoke_async(Payload::with_data("users.get", &json!(1))))
+        .error
+        .unwrap();
+    assert_eq!(error.message, "Forbidden");
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["v2 before users.get", "v2 after", "v2 before users.get", "v2 after"]
+    );
+}
@@ -0,0 +1,98 @@
+use axor::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct AccountId(u32);
+
+#[agent]
+struct BankAgent;
+
+#[agent_impl]
+impl BankAgent {
+    #[operation]
+    fn transfer(&self, from: AccountId, to: AccountId, amount: u64) -> String {
+        format!("{} from {} to {}", amount, from.0, to.0)
+    }
+
+    #[operation]
+    fn label(&self, id: AccountId, prefix: Option<String>) -> String {
+        format!("{}{}", prefix.unwrap_or_default(), id.0)
+    }
+}
+
+fn context() -> AxorContext {
+    let context = AxorContext::new();
+    context.register(BankAgent);
+    context.init();
+    context
+}
+
+#[test]
+fn named_arguments() {
+    let payload = Payload::with_data(
+        "BankAgent.transfer",
+        &json!({ "from": 1, "to": 2, "amount": 50 }),
+    );
+    let response = context().invoke(payload);
+    assert!(response.success);
+    assert_eq!(response.data, Some(json!("50 from 1 to 2")));
+}
+
+#[test]
+fn positional_arguments() {
+    let payload = Payload::with_data("BankAgent.transfer", &json!([3, 4, 10]));
+    let response = context().invoke(payload);
+    assert!(response.success);
+    assert_eq!(response.data, Some(json!("10 from 3 to 4")));
+}
+
+#[test]
+fn optional_argument_can_be_omitted() {
+    let context = context();
+
+    let response = context.invoke(Payload::with_data("BankAgent.label", &json!({ "id": 5 })));
+    assert_eq!(response.data, Some(json!("5")));
+
+    let response = context.invoke(Payload::with_data("BankAgent.label", &json!([5, "#"])));
+    assert_eq!(response.data, Some(json!("#5")));
+}
+
+#[test]
+fn invalid_arguments() {
+    let context = context();
+
+    let response = context.invoke(Payload::with_data(
+        "BankAgent.transfer",
+        &json!({ "from": 1, "to": 2 }),
+    ));
+    let error = response.error.unwrap();
+    assert_eq!(error.code, ErrorCode::InvalidInput);
+    assert!(error.message.contains("amount"));
+
+    let response = context.invoke(Payload::with_data("BankAgent.transfer", &json!([1, 2, 3, 4])));
+    assert_eq!(response.error.unwrap().code, ErrorCode::InvalidInput);
+
+    let response = context.invoke(Payload::with_data("BankAgent.transfer", &"1,2,3"));
+    assert_eq!(response.error.unwrap().code, ErrorCode::InvalidInput);
+}
+
+#[test]
+fn params_are_described() {
+    let context = context();
+    let agent = context.resolve::<BankAgent>();
+    let operations = agent.operations();
+    let transfer = operations.iter().find(|op| op.name == "transfer").unwrap();
+    let params: Vec<_> = transfer
+        .params
+        .iter()
+        .map(|param| (param.name, param.type_name))
+        .collect();
+    assert_eq!(
+        params,
+        vec![("from", "AccountId"), ("to", "AccountId"), ("amount", "u64")]
+    );
+
+    let label = operations.iter().find(|op| op.name == "label").unwrap();
+    assert_eq!(label.params[1].type_name, "Option<String>");
+}
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use axor::prelude::*;
+use serde_json::json;
+
+trait Validator: Send + Sync {
+    fn check(&self, value: &str) -> Option<String>;
+}
+
+struct NotEmpty;
+
+impl Validator for NotEmpty {
+    fn check(&self, value: &str) -> Option<String> {
+        value.is_empty().then(|| "empty".to_string())
+    }
+}
+
+struct MaxLength(usize);
+
+impl Validator for MaxLength {
+    fn check(&self, value: &str) -> Option<String> {
+        (value.len() > self.0).then(|| format!("longer than {}", self.0))
+    }
+}
+
+#[agent]
+struct FormAgent {
+    validators: InjectAll<dyn Validator>,
+}
+
+#[agent_impl]
+impl FormAgent {
+    #[operation]
+    fn validate(&self, value: String) -> Vec<String> {
+        self.validators
+            .resolve()
+            .iter()
+            .filter_map(|validator| validator.check(&value))
+            .collect()
+    }
+}
+
+#[test]
+fn collects_every_registration_in_order() {
+    let context = AxorContext::builder()
+        .agent(FormAgent::default())
+        .service_into::<dyn Validator>(Arc::new(NotEmpty))
+        .service_into::<dyn Validator>(Arc::new(MaxLength(0)))
+        .build()
+        .unwrap();
+
+    let result = context.invoke(Payload::with_data("FormAgent.validate", &json!("")));
+    assert_eq!(result.data, Some(json!(["empty"])));
+    let result = context.invoke(Payload::with_data("FormAgent.validate", &json!("abc")));
+    assert_eq!(result.data, Some(json!(["longer than 0"])));
+}
+
+#[test]
+fn registrations_are_additive() {
+    let context = AxorContext::new();
+    assert!(context.get_all::<dyn Validator>().is_empty());
+
+    context.register_into::<dyn Validator>(Arc::new(MaxLength(2)));
+    context.register_into::<dyn Validator>(Arc::new(MaxLength(1)));
+    let messages: Vec<_> = context
+        .get_all::<dyn Validator>()
+        .iter()
+        .filter_map(|validator| validator.check("abc"))
+        .collect();
+    assert_eq!(messages, vec!["longer than 2", "longer than 1"]);
+}
+
+#[test]
+fn empty_collections_are_valid() {
+    let context = AxorContext::builder()
+        .agent(FormAgent::default())
+        .build()
+        .unwrap();
+
+    assert!(context.resolve::<FormAgent>().validate("".into()).is_empty());
+    let manifest = serde_json::to_value(context.manifest()).unwrap();
+    assert_eq!(manifest["agents"][0]["dependencies"][0]["kind"], "all");
+}
@@ -0,0 +1,105 @@
+use axor::prelude::*;
+use axor::{BuildError, MissingDependency};
+use serde_json::json;
+
+#[interface]
+trait Database: Send + Sync {
+    fn url(&self) -> &str;
+}
+
+struct Postgres(&'static str);
+
+impl Database for Postgres {
+    fn url(&self) -> &str {
+        self.0
+    }
+}
+
+#[agent]
+struct ReportAgent {
+    #[inject(name = "primary")]
+    primary: Inject<dyn Database>,
+    #[inject(name = "replica")]
+    replica: Inject<dyn Database>,
+}
+
+#[agent_impl]
+impl ReportAgent {
+    #[operation]
+    fn urls(&self) -> Vec<String> {
+        vec![
+            self.primary.resolve().url().to_string(),
+            self.replica.resolve().url().to_string(),
+        ]
+    }
+}
+
+#[test]
+fn named_services_are_injected_by_qualifier() {
+    let context = AxorContext::builder()
+        .agent(ReportAgent::default())
+        .named_service_as::<dyn Database, _>("primary", Postgres("pg://primary"))
+        .named_service_as::<dyn Database, _>("replica", Postgres("pg://replica"))
+        .build()
+        .unwrap();
+
+    let result = context.invoke(Payload::new("ReportAgent.urls"));
+    assert_eq!(result.data, Some(json!(["pg://primary", "pg://replica"])));
+}
+
+#[test]
+fn named_and_unnamed_services_coexist() {
+    let context = AxorContext::new();
+    context.register_as::<dyn Database, _>(Postgres("pg://default"));
+    context.register_named_as::<dyn Database, _>("replica", Postgres("pg://replica"));
+
+    assert_eq!(context.resolve::<dyn Database>().url(), "pg://default");
+    assert_eq!(
+        context.resolve_named::<dyn Database>("replica").url(),
+        "pg://replica"
+    );
+    assert!(context.get_named_service::<dyn Database>("primary").is_none());
+}
+
+#[test]
+fn missing_named_services_are_reported() {
+    let error = AxorContext::builder()
+        .agent(ReportAgent::default())
+        .named_service_as::<dyn Database, _>("primary", Postgres("pg://primary"))
+        .build()
+        .err()
+        .unwrap();
+
+    let BuildError::InvalidDependencies(invalid) = &error else {
+        panic!("unexpected error: {}", error);
+    };
+    assert_eq!(
+        invalid.missing,
+        vec![MissingDependency {
+            agent: "ReportAgent".into(),
+            field: "replica",
+            name: Some("replica"),
+            type_name: std::any::type_name::<dyn Database>(),
+        }]
+    );
+    assert!(error.to_string().contains("named \"replica\""));
+}
+
+#[test]
+fn manifest_lists_qualifiers() {
+    let context = AxorContext::new();
+    context.register(ReportAgent::default());
+
+    let manifest = serde_json::to_value(context.manifest()).unwrap();
+    let dependencies = &manifest["agents"][0]["dependencies"];
+    assert_eq!(dependencies[1]["field"], "replica");
+    assert_eq!(dependencies[1]["name"], "replica");
+}
+
+#[test]
+#[should_panic(expected = "registered names: [\"primary\"]")]
+fn resolve_named_lists_registered_names() {
+    let context = AxorContext::new();
+    context.register_named_as::<dyn Database, _>("primary", Postgres("pg://primary"));
+    context.resolve_named::<dyn Database>("replica");
+}
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use axor::prelude::*;
+use serde_json::json;
+
+trait Metrics: Send + Sync {
+    fn prefix(&self) -> &str;
+}
+
+struct Prometheus;
+
+impl Metrics for Prometheus {
+    fn prefix(&self) -> &str {
+        "prom"
+    }
+}
+
+#[agent]
+struct CheckoutAgent {
+    metrics: InjectOptional<Arc<dyn Metrics>>,
+}
+
+#[agent_impl]
+impl CheckoutAgent {
+    #[operation]
+    fn pay(&self) -> String {
+        match self.metrics.resolve() {
+            Some(metrics) => format!("{}:paid", metrics.prefix()),
+            None => "paid".into(),
+        }
+    }
+}
+
+#[agent]
+struct ParentAgent {
+    #[inject(lazy)]
+    child: Inject<ChildAgent>,
+}
+
+#[agent_impl]
+impl ParentAgent {
+    #[operation]
+    fn family(&self) -> String {
+        format!("parent of {}", self.child.resolve().label())
+    }
+
+    fn label(&self) -> &'static str {
+        "parent"
+    }
+}
+
+#[agent]
+struct ChildAgent {
+    parent: Inject<ParentAgent>,
+}
+
+#[agent_impl]
+impl ChildAgent {
+    #[operation]
+    fn family(&self) -> String {
+        format!("child of {}", self.parent.resolve().label())
+    }
+
+    fn label(&self) -> &'static str {
+        "child"
+    }
+}
+
+#[test]
+fn optional_dependencies_may_be_absent() {
+    let context = AxorContext::builder()
+        .agent(CheckoutAgent::default())
+        .build()
+        .unwrap();
+    let result = context.invoke(Payload::new("CheckoutAgent.pay"));
+    assert_eq!(result.data, Some(json!("paid")));
+
+    let context = AxorContext::builder()
+        .agent(CheckoutAgent::default())
+        .service::<Arc<dyn Metrics>>(Arc::new(Prometheus))
+        .build()
+        .unwrap();
+    let result = context.invoke(Payload::new("CheckoutAgent.pay"));
+    assert_eq!(result.data, Some(json!("prom:paid")));
+}
+
+#[test]
+fn lazy_injection_breaks_cycles() {
+    let context = AxorContext::builder()
+        .agent(ParentAgent::default())
+        .agent(ChildAgent::default())
+        .build()
+        .unwrap();
+
+    let result = context.invoke(Payload::new("ParentAgent.family"));
+    assert_eq!(result.data, Some(json!("parent of child")));
+    let result = context.invoke(Payload::new("ChildAgent.family"));
+    assert_eq!(result.data, Some(json!("child of parent")));
+}
+
+#[test]
+fn lazy_injection_sees_later_registrations() {
+    let context = AxorContext::new();
+    context.register(ParentAgent::default());
+    context.init();
+
+    context.register(ChildAgent::default());
+    context.init();
+    assert_eq!(context.resolve::<ParentAgent>().family(), "parent of child");
+}
+
+#[test]
+#[should_panic(expected = "is not registered")]
+fn lazy_injection_panics_on_first_access_when_missing() {
+    let context = AxorContext::new();
+    context.register(ParentAgent::default());
+    context.init();
+    context.resolve::<ParentAgent>().family();
+}
+
+#[test]
+fn manifest_flags_soft_dependencies() {
+    let context = AxorContext::new();
+    context.register(CheckoutAgent::default());
+    context.register(ParentAgent::default());
+
+    let manifest = serde_json::to_value(context.manifest()).unwrap();
+    assert_eq!(manifest["agents"][0]["dependencies"][0]["kind"], "optional");
+    assert_eq!(manifest["agents"][1]["dependencies"][0]["lazy"], true);
+}
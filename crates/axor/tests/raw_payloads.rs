@@ -0,0 +1,175 @@
+use std::ops::ControlFlow;
+
+use axor::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Document {
+    title: String,
+    lines: Vec<String>,
+}
+
+#[agent]
+struct DocumentAgent;
+
+#[agent_impl]
+impl DocumentAgent {
+    #[operation]
+    fn count(&self, document: Document) -> usize {
+        document.lines.len()
+    }
+
+    #[operation]
+    fn line(&self, document: Document, index: usize, prefix: Option<String>) -> String {
+        format!("{}{}", prefix.unwrap_or_default(), document.lines[index])
+    }
+
+    #[operation]
+    fn title(&self, Json(document): Json<Document>) -> String {
+        document.title
+    }
+}
+
+fn context() -> AxorContext {
+    let context = AxorContext::new();
+    context.register(DocumentAgent);
+    context.init();
+    context
+}
+
+fn payload(json: serde_json::Value) -> Payload {
+    Payload::from_slice(&serde_json::to_vec(&json).unwrap()).unwrap()
+}
+
+fn document() -> serde_json::Value {
+    json!({ "title": "Notes", "lines": ["first", "second"] })
+}
+
+#[test]
+fn data_is_kept_raw() {
+    let payload = payload(json!({ "name": "DocumentAgent.count", "data": document(), "success": false }));
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(payload.raw_data().unwrap().get()).unwrap(),
+        document()
+    );
+    assert_eq!(payload.data().unwrap().into_owned(), document());
+    assert!(payload.input_as::<Document>().is_ok());
+}
+
+#[test]
+fn single_input() {
+    let context = context();
+
+    let response = context.invoke(payload(json!({
+        "name": "DocumentAgent.count", "data": document(), "success": false
+    })));
+    assert_eq!(response.data, Some(json!(2)));
+
+    let response = context.invoke(payload(json!({
+        "name": "DocumentAgent.title", "data": document(), "success": false
+    })));
+    assert_eq!(response.data, Some(json!("Notes")));
+}
+
+#[test]
+fn named_and_positional_arguments() {
+    let context = context();
+
+    let response = context.invoke(payload(json!({
+        "name": "DocumentAgent.line",
+        "data": { "document": document(), "index": 1, "prefix": "> " },
+        "success": false
+    })));
+    assert_eq!(response.data, Some(json!("> second")));
+
+    let response = context.invoke(payload(json!({
+        "name": "DocumentAgent.line", "data": [document(), 0], "success": false
+    })));
+    assert_eq!(response.data, Some(json!("first")));
+}
+
+#[test]
+fn invalid_inputs() {
+    let context = context();
+
+    let response = context.invoke(payload(json!({
+        "name": "DocumentAgent.count", "success": false
+    })));
+    let error = response.error.unwrap();
+    assert_eq!(error.code, ErrorCode::InvalidInput);
+    assert_eq!(error.message, "Missing input data");
+
+    let response = context.invoke(payload(json!({
+        "name": "DocumentAgent.line", "data": { "document": document() }, "success": false
+    })));
+    assert_eq!(response.error.unwrap().message, "Missing argument `index`");
+
+    let response = context.invoke(payload(json!({
+        "name": "DocumentAgent.line", "data": [document(), 0, "", 1], "success": false
+    })));
+    assert_eq!(
+        response.error.unwrap().message,
+        "Expected at most 3 arguments, got 4"
+    );
+
+    let response = context.invoke(payload(json!({
+        "name": "DocumentAgent.line", "data": { "document": document(), "index": "one" }, "success": false
+    })));
+    assert!(response.error.unwrap().message.starts_with("Invalid argument `index`"));
+}
+
+#[test]
+fn serializes_raw_data_back() {
+    let payload = payload(json!({
+        "id": "7",
+        "name": "DocumentAgent.count",
+        "data": document(),
+        "metadata": { "tenant": "acme" },
+        "success": false
+    }));
+
+    let value = serde_json::to_value(&payload).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "id": "7",
+            "name": "DocumentAgent.count",
+            "data": document(),
+            "metadata": { "tenant": "acme" },
+            "success": false
+        })
+    );
+
+    let parsed: Payload = serde_json::from_value(value).unwrap();
+    assert!(parsed.raw_data().is_none());
+    assert_eq!(parsed.data().unwrap().into_owned(), document());
+}
+
+/// Keeps only the first line of the documents sent to `count`.
+struct Truncate;
+
+impl Middleware for Truncate {
+    fn before(&self, payload: &mut Payload) -> ControlFlow<InvokeResult> {
+        let mut document: Document = payload.input_as().unwrap();
+        document.lines.truncate(1);
+        payload.set_data(Some(serde_json::to_value(document).unwrap()));
+        ControlFlow::Continue(())
+    }
+}
+
+#[test]
+fn middlewares_rewrite_raw_data() {
+    let context = context();
+    context.register_operation_middleware("DocumentAgent.count", Truncate);
+
+    let mut payload = payload(json!({
+        "name": "DocumentAgent.count", "data": document(), "success": false
+    }));
+    assert!(payload.has_data());
+    let response = context.invoke(payload.clone());
+    assert_eq!(response.data, Some(json!(1)));
+
+    payload.set_data(None);
+    assert!(!payload.has_data() && payload.raw_data().is_none());
+}
@@ -0,0 +1,109 @@
+use axor::prelude::*;
+use serde::Serialize;
+use serde_json::json;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+#[derive(Debug)]
+enum UserError {
+    NotFound(u32),
+}
+
+impl IntoInvokeError for UserError {
+    fn into_invoke_error(self) -> InvokeError {
+        match self {
+            UserError::NotFound(id) => {
+                InvokeError::operation_failed(format!("User {} not found", id))
+                    .with_details(json!({ "id": id }))
+            }
+        }
+    }
+}
+
+#[agent]
+struct UserAgent;
+
+#[agent_impl]
+impl UserAgent {
+    #[operation]
+    fn get(&self, id: u32) -> Result<User, UserError> {
+        if id == 1 {
+            Ok(User {
+                id,
+                name: "Alice".into(),
+            })
+        } else {
+            Err(UserError::NotFound(id))
+        }
+    }
+
+    #[operation]
+    fn check(&self, id: u32) -> anyhow::Result<bool> {
+        if id == 0 {
+            anyhow::bail!("Invalid id");
+        }
+        Ok(true)
+    }
+
+    #[operation]
+    fn read(&self, path: String) -> std::io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+}
+
+fn context() -> AxorContext {
+    let context = AxorContext::new();
+    context.register(UserAgent);
+    context.init();
+    context
+}
+
+#[test]
+fn direct_call_keeps_result_type() {
+    let context = context();
+    let agent = context.resolve::<UserAgent>();
+    assert_eq!(agent.get(1).unwrap().name, "Alice");
+    assert!(matches!(agent.get(2), Err(UserError::NotFound(2))));
+}
+
+#[test]
+fn ok_value_is_returned_as_data() {
+    let response = context().invoke(Payload::with_data("UserAgent.get", &1));
+    assert!(response.success);
+    assert_eq!(response.data, Some(json!({ "id": 1, "name": "Alice" })));
+}
+
+#[test]
+fn custom_error_is_mapped() {
+    let response = context().invoke(Payload::with_data("UserAgent.get", &7));
+    assert!(!response.success);
+    assert!(response.data.is_none());
+    let error = response.error.unwrap();
+    assert_eq!(error.code, ErrorCode::OperationFailed);
+    assert_eq!(error.message, "User 7 not found");
+    assert_eq!(error.details, Some(json!({ "id": 7 })));
+}
+
+#[test]
+fn anyhow_error_is_mapped() {
+    let response = context().invoke(Payload::with_data("UserAgent.check", &3));
+    assert!(response.success);
+    assert_eq!(response.data, Some(json!(true)));
+
+    let response = context().invoke(Payload::with_data("UserAgent.check", &0));
+    assert!(!response.success);
+    let error = response.error.unwrap();
+    assert_eq!(error.code, ErrorCode::OperationFailed);
+    assert_eq!(error.message, "Invalid id");
+}
+
+#[test]
+fn io_error_is_mapped() {
+    let response = context().invoke(Payload::with_data("UserAgent.read", &"/missing/axor/file"));
+    assert!(!response.success);
+    assert_eq!(response.error.unwrap().code, ErrorCode::OperationFailed);
+}
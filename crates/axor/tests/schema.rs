@@ -0,0 +1,147 @@
+#![cfg(feature = "schema")]
+
+use axor::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+#[agent]
+struct UserAgent;
+
+#[agent_impl(schema)]
+impl UserAgent {
+    #[operation]
+    fn create(&self, user: User) -> anyhow::Result<User> {
+        Ok(user)
+    }
+
+    #[operation]
+    fn rename(&self, id: u32, name: String, reason: Option<String>) -> bool {
+        let _ = (id, name, reason);
+        true
+    }
+
+    #[operation]
+    fn clear(&self) {}
+
+    #[operation]
+    fn update(&self, invocation: &InvokeContext, Json(user): Json<User>) -> u32 {
+        let _ = invocation;
+        user.id
+    }
+
+    #[operation]
+    fn list(&self, invocation: &InvokeContext, page: Page, filter: User) -> Vec<User> {
+        let _ = (invocation, page.0, filter);
+        Vec::new()
+    }
+}
+
+/// Custom input extractor, described by hand.
+struct Page(u32);
+
+impl<'a> FromInvocation<'a> for Page {
+    const INPUT: bool = true;
+
+    fn from_invocation(invocation: &'a Invocation<'a>, position: usize) -> Result<Self, InvokeError> {
+        invocation.input(position).map(Page)
+    }
+
+    fn describe(schema: ParamsSchema, name: &str, required: bool) -> ParamsSchema {
+        schema.param_schema(name, json!({ "type": "integer", "minimum": 1 }), required)
+    }
+}
+
+/// Input without `JsonSchema`, for agents not opting into schemas.
+#[derive(Deserialize)]
+struct Plain {
+    value: u32,
+}
+
+#[agent]
+struct PlainAgent;
+
+#[agent_impl]
+impl PlainAgent {
+    #[operation]
+    fn run(&self, plain: Plain) -> u32 {
+        plain.value
+    }
+}
+
+fn operation(name: &str) -> OperationDescriptor {
+    UserAgent
+        .operations()
+        .into_iter()
+        .find(|op| op.name == name)
+        .unwrap()
+}
+
+#[test]
+fn single_input_and_result_output_schemas() {
+    let create = operation("create");
+    let input = create.input_schema.unwrap();
+    assert_eq!(input["title"], "User");
+    assert_eq!(input["properties"]["name"]["type"], "string");
+
+    // The `Ok` type is described, not the `Result`
+    let output = create.output_schema.unwrap();
+    assert_eq!(output["title"], "User");
+}
+
+#[test]
+fn named_arguments_schema() {
+    let rename = operation("rename");
+    let input = rename.input_schema.unwrap();
+    assert_eq!(input["type"], "object");
+    assert_eq!(input["properties"]["id"]["type"], "integer");
+    assert_eq!(input["required"], json!(["id", "name"]));
+    assert_eq!(rename.output_schema.unwrap()["type"], "boolean");
+}
+
+#[test]
+fn no_input_no_output() {
+    let clear = operation("clear");
+    assert!(clear.input_schema.is_none());
+    assert!(clear.output_schema.is_none());
+}
+
+#[test]
+fn json_bodies_are_described_by_their_content() {
+    let update = operation("update");
+    assert_eq!(update.params[0].name, "user");
+    assert_eq!(update.input_schema.unwrap()["title"], "User");
+}
+
+#[test]
+fn schemas_are_in_manifest() {
+    let context = AxorContext::new();
+    context.register(UserAgent);
+    let manifest = serde_json::to_value(context.manifest()).unwrap();
+    let operations = manifest["agents"][0]["operations"].as_array().unwrap();
+    assert!(operations
+        .iter()
+        .any(|op| op["input_schema"]["title"] == "User"));
+}
+
+#[test]
+fn extractors_describe_themselves() {
+    let input = operation("list").input_schema.unwrap();
+    let properties = input["properties"].as_object().unwrap();
+    assert_eq!(properties.keys().collect::<Vec<_>>(), ["filter", "page"]);
+    assert_eq!(input["properties"]["page"]["minimum"], 1);
+}
+
+#[test]
+fn schemas_are_opt_in() {
+    let run = PlainAgent.operations().remove(0);
+    assert_eq!(run.params[0].name, "plain");
+    assert!(run.input_schema.is_none());
+    assert!(run.output_schema.is_none());
+}
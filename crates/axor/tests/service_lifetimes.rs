@@ -0,0 +1,136 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use axor::prelude::*;
+use serde_json::json;
+
+/// Counts how many instances a factory built.
+#[derive(Default)]
+struct Counter(AtomicUsize);
+
+impl Counter {
+    fn next(&self) -> usize {
+        self.0.fetch_add(1, Ordering::SeqCst) + 1
+    }
+}
+
+struct Transaction {
+    id: usize,
+}
+
+#[agent]
+struct OrderAgent {
+    transaction: Inject<Transaction>,
+}
+
+#[agent_impl]
+impl OrderAgent {
+    #[operation]
+    fn place(&self) -> (usize, usize) {
+        (self.transaction.resolve().id, self.transaction.resolve().id)
+    }
+
+    #[operation]
+    async fn place_later(&self) -> (usize, usize) {
+        let first = self.transaction.resolve().id;
+        yield_now().await;
+        (first, self.transaction.resolve().id)
+    }
+}
+
+/// Yields once so the operation is polled again.
+async fn yield_now() {
+    let mut yielded = false;
+    std::future::poll_fn(|cx| {
+        if yielded {
+            std::task::Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            std::task::Poll::Pending
+        }
+    })
+    .await
+}
+
+fn transactions(context: &AxorContext) -> Transaction {
+    Transaction {
+        id: context.resolve::<Counter>().next(),
+    }
+}
+
+#[test]
+fn singleton_factories_are_lazy_and_shared() {
+    let context = AxorContext::new();
+    context.register_service(Counter::default());
+    context.register_factory(ServiceLifetime::Singleton, transactions);
+
+    assert_eq!(context.resolve::<Counter>().0.load(Ordering::SeqCst), 0);
+    assert_eq!(context.resolve::<Transaction>().id, 1);
+    assert_eq!(context.resolve::<Transaction>().id, 1);
+}
+
+#[test]
+fn transient_factories_build_on_every_resolve() {
+    let context = AxorContext::new();
+    context.register_service(Counter::default());
+    context.register_factory(ServiceLifetime::Transient, transactions);
+
+    assert_eq!(context.resolve::<Transaction>().id, 1);
+    assert_eq!(context.get_service::<Transaction>().unwrap().id, 2);
+}
+
+#[test]
+fn scoped_factories_build_once_per_invocation() {
+    let context = AxorContext::builder()
+        .agent(OrderAgent::default())
+        .service(Counter::default())
+        .factory(ServiceLifetime::Scoped, transactions)
+        .build()
+        .unwrap();
+
+    let first = context.invoke(Payload::new("OrderAgent.place"));
+    assert_eq!(first.data, Some(json!([1, 1])));
+    let second = context.invoke(Payload::new("OrderAgent.place"));
+    assert_eq!(second.data, Some(json!([2, 2])));
+
+    let (a, b) = context.scope(|| {
+        (
+            context.resolve::<Transaction>().id,
+            context.resolve::<Transaction>().id,
+        )
+    });
+    assert_eq!((a, b), (3, 3));
+}
+
+#[test]
+fn scopes_follow_async_invocations() {
+    let context = AxorContext::builder()
+        .agent(OrderAgent::default())
+        .service(Counter::default())
+        .factory(ServiceLifetime::Scoped, transactions)
+        .build()
+        .unwrap();
+
+    let result =
+        futures::executor::block_on(context.invoke_async(Payload::new("OrderAgent.place_later")));
+    assert_eq!(result.data, Some(json!([1, 1])));
+}
+
+#[test]
+#[should_panic(expected = "outside of an invocation scope")]
+fn scoped_services_need_a_scope() {
+    let context = AxorContext::new();
+    context.register_service(Counter::default());
+    context.register_factory(ServiceLifetime::Scoped, transactions);
+    context.resolve::<Transaction>();
+}
+
+#[test]
+fn factories_satisfy_validation() {
+    let context = AxorContext::new();
+    context.register(OrderAgent::default());
+    context.register_factory(ServiceLifetime::Transient, |_| Transaction { id: 7 });
+    context.init();
+
+    assert_eq!(context.resolve::<OrderAgent>().place(), (7, 7));
+}
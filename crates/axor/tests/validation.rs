@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use axor::prelude::*;
+use axor::{BuildError, MissingDependency};
+
+struct Database;
+
+trait Mailer: Send + Sync {}
+
+#[agent]
+struct OrderAgent {
+    users: Inject<UserAgent>,
+    database: Inject<Database>,
+}
+
+#[agent_impl]
+impl OrderAgent {}
+
+#[agent(name = "users")]
+struct UserAgent {
+    orders: Inject<OrderAgent>,
+    mailer: Inject<Arc<dyn Mailer>>,
+}
+
+#[agent_impl]
+impl UserAgent {}
+
+#[agent]
+struct AuditAgent {
+    orders: Inject<OrderAgent>,
+}
+
+#[agent_impl]
+impl AuditAgent {}
+
+#[test]
+fn validate_reports_everything_at_once() {
+    let context = AxorContext::new();
+    context.register(OrderAgent::default());
+    context.register(UserAgent::default());
+    context.register(AuditAgent::default());
+
+    let error = context.validate().unwrap_err();
+    assert_eq!(
+        error.missing,
+        vec![
+            MissingDependency {
+                agent: "OrderAgent".into(),
+                field: "database",
+                name: None,
+                type_name: std::any::type_name::<Database>(),
+            },
+            MissingDependency {
+                agent: "users".into(),
+                field: "mailer",
+                name: None,
+                type_name: std::any::type_name::<Arc<dyn Mailer>>(),
+            },
+        ]
+    );
+    assert_eq!(error.cycles, vec![vec!["OrderAgent", "users", "OrderAgent"]]);
+
+    let report = error.to_string();
+    assert!(report.contains("OrderAgent.database requires"));
+    assert!(report.contains("dependency cycle: OrderAgent -> users -> OrderAgent"));
+}
+
+#[test]
+fn build_rejects_cycles() {
+    let error = AxorContext::builder()
+        .agent(UserAgent::default())
+        .agent(OrderAgent::default())
+        .service(Database)
+        .service::<Arc<dyn Mailer>>(Arc::new(NoMailer))
+        .build()
+        .err()
+        .unwrap();
+
+    let BuildError::InvalidDependencies(invalid) = error else {
+        panic!("unexpected error: {}", error);
+    };
+    assert!(invalid.missing.is_empty());
+    assert_eq!(invalid.cycles, vec![vec!["users", "OrderAgent", "users"]]);
+}
+
+struct NoMailer;
+
+impl Mailer for NoMailer {}
+
+#[test]
+fn try_init_injects_valid_graphs() {
+    let context = AxorContext::new();
+    context.register(AuditAgent::default());
+    assert!(context.try_init().is_err());
+
+    let context = AxorContext::new();
+    context.register_service(Database);
+    assert!(context.validate().is_ok());
+    assert!(context.try_init().is_ok());
+}
+
+#[test]
+#[should_panic(expected = "`validation::Database` is not registered")]
+fn resolve_names_the_missing_type() {
+    AxorContext::new().resolve::<Database>();
+}
+
+#[test]
+#[should_panic(expected = "Dependency not injected: `validation::Database`")]
+fn unresolved_inject_names_its_type() {
+    Inject::<Database>::default().resolve();
+}